@@ -0,0 +1,14 @@
+-- Create message_embeddings table
+-- Stores Gemini text-embedding-004 vectors (768 dimensions) for semantic search over messages.
+-- Requires the pgvector extension (available on Supabase and most managed Postgres providers).
+CREATE EXTENSION IF NOT EXISTS vector;
+
+CREATE TABLE IF NOT EXISTS message_embeddings (
+  message_id UUID PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+  embedding vector(768) NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Approximate nearest-neighbour index for cosine distance (<=>)
+CREATE INDEX IF NOT EXISTS idx_message_embeddings_embedding
+  ON message_embeddings USING hnsw (embedding vector_cosine_ops);
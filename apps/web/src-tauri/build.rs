@@ -2,6 +2,15 @@ use std::env;
 use std::fs;
 use std::path::Path;
 
+use sha2::{Digest, Sha256};
+
+fn hash_file(path: &Path) -> String {
+  let mut file = fs::File::open(path).expect("Failed to open bundled model for hashing");
+  let mut hasher = Sha256::new();
+  std::io::copy(&mut file, &mut hasher).expect("Failed to hash bundled model");
+  format!("{:x}", hasher.finalize())
+}
+
 fn main() {
   // Copy model file from project root to src-tauri/models for bundling
   // This MUST happen BEFORE tauri_build::build() so Tauri can find it
@@ -14,6 +23,9 @@ fn main() {
     .and_then(|p| p.parent()) // apps
     .and_then(|p| p.parent()); // project root
   
+  // Size and SHA-256 of the bundled model, embedded for get_bundled_model_info
+  let mut bundled_model: Option<(u64, String)> = None;
+
   if let Some(root) = project_root {
     // Copy model file
     let model_source = root.join("models").join("ggml-base.en.bin");
@@ -32,10 +44,11 @@ fn main() {
       
       // Copy the model file
       match fs::copy(&model_source, &model_dest) {
-        Ok(_) => {
+        Ok(size) => {
           println!("cargo:warning=Successfully copied model for bundling");
           // Tell Cargo to rerun if the source model changes
           println!("cargo:rerun-if-changed={}", model_source.display());
+          bundled_model = Some((size, hash_file(&model_dest)));
         }
         Err(e) => {
           panic!("Failed to copy model file from {:?} to {:?}: {}", model_source, model_dest, e);
@@ -68,6 +81,10 @@ fn main() {
     println!("cargo:warning=Could not determine project root from: {:?}", src_tauri_dir);
   }
   
+  let (model_size, model_hash) = bundled_model.unwrap_or_default();
+  println!("cargo:rustc-env=BUNDLED_MODEL_SIZE={}", model_size);
+  println!("cargo:rustc-env=BUNDLED_MODEL_SHA256={}", model_hash);
+
   // Now run Tauri build - it will find the model in src-tauri/models/
   tauri_build::build();
 }
@@ -0,0 +1,34 @@
+use serde_json::{json, Value};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_posthog::PostHogExt;
+
+/// PostHog project key baked in at build time (empty = analytics disabled)
+pub fn posthog_api_key() -> String {
+    option_env!("POSTHOG_API_KEY").unwrap_or("").to_string()
+}
+
+/// Capture a domain event (e.g. "transcription_started") from the Rust side
+/// Goes through the posthog plugin, so events share its client and distinct id with the
+/// ones the UI sends. No-op when no PostHog key was configured at build time.
+#[tauri::command]
+pub async fn track_event<R: Runtime>(
+    app: AppHandle<R>,
+    name: String,
+    properties: Option<Value>,
+) -> Result<(), String> {
+    if posthog_api_key().is_empty() {
+        return Ok(());
+    }
+
+    let mut properties = match properties {
+        Some(Value::Object(map)) => Value::Object(map),
+        Some(Value::Null) | None => json!({}),
+        Some(other) => json!({ "value": other }),
+    };
+    properties["app_version"] = json!(app.package_info().version.to_string());
+
+    app.posthog()
+        .capture(&name, Some(properties))
+        .await
+        .map_err(|e| format!("Failed to send analytics event '{}': {}", name, e))
+}
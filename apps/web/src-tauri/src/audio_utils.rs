@@ -1,4 +1,5 @@
-use tauri::{AppHandle, Manager};
+use rustfft::{num_complex::Complex, FftPlanner};
+use tauri::AppHandle;
 
 #[tauri::command]
 pub async fn save_audio_buffer(
@@ -6,12 +7,7 @@ pub async fn save_audio_buffer(
     audio_data: Vec<u8>,
     filename: String,
 ) -> Result<String, String> {
-    let app_data_dir = app.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
-    
-    let audio_dir = app_data_dir.join("audio_cache");
-    std::fs::create_dir_all(&audio_dir)
-        .map_err(|e| format!("Failed to create audio directory: {}", e))?;
+    let audio_dir = crate::paths::ensure_audio_cache_dir(&app)?;
     
     let file_path = audio_dir.join(&filename);
     
@@ -21,6 +17,87 @@ pub async fn save_audio_buffer(
     Ok(file_path.to_string_lossy().to_string())
 }
 
+/// Encode raw little-endian PCM (e.g. captured with the Web Audio API) as a WAV file in
+/// the audio cache and return its path. 16-bit is read as signed integers, 32-bit as floats.
+#[tauri::command]
+pub async fn pcm_to_wav(
+    app: AppHandle,
+    pcm: Vec<u8>,
+    sample_rate: u32,
+    channels: u16,
+    bits_per_sample: u16,
+    filename: String,
+) -> Result<String, String> {
+    if !matches!(bits_per_sample, 16 | 32) {
+        return Err(format!(
+            "Unsupported bit depth: {} (expected 16 or 32)",
+            bits_per_sample
+        ));
+    }
+    if channels == 0 || channels > 8 {
+        return Err(format!("Unsupported channel count: {} (expected 1-8)", channels));
+    }
+    if sample_rate == 0 {
+        return Err("Sample rate must be greater than zero".to_string());
+    }
+    let frame_bytes = channels as usize * (bits_per_sample / 8) as usize;
+    if pcm.len() % frame_bytes != 0 {
+        return Err(format!(
+            "PCM length {} is not a multiple of the frame size ({} bytes)",
+            pcm.len(),
+            frame_bytes
+        ));
+    }
+
+    let file_name = std::path::Path::new(&filename)
+        .file_name()
+        .ok_or("Invalid filename")?
+        .to_string_lossy()
+        .to_string();
+    let file_name = if file_name.to_lowercase().ends_with(".wav") {
+        file_name
+    } else {
+        format!("{}.wav", file_name)
+    };
+
+    let audio_dir = crate::paths::ensure_audio_cache_dir(&app)?;
+
+    let file_path = audio_dir.join(&file_name);
+
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample,
+        sample_format: if bits_per_sample == 32 {
+            hound::SampleFormat::Float
+        } else {
+            hound::SampleFormat::Int
+        },
+    };
+    let mut writer = hound::WavWriter::create(&file_path, spec)
+        .map_err(|e| format!("Failed to create WAV file: {}", e))?;
+
+    if bits_per_sample == 16 {
+        for bytes in pcm.chunks_exact(2) {
+            writer
+                .write_sample(i16::from_le_bytes([bytes[0], bytes[1]]))
+                .map_err(|e| format!("Failed to write WAV sample: {}", e))?;
+        }
+    } else {
+        for bytes in pcm.chunks_exact(4) {
+            writer
+                .write_sample(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .map_err(|e| format!("Failed to write WAV sample: {}", e))?;
+        }
+    }
+
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to finalize WAV file: {}", e))?;
+
+    Ok(file_path.to_string_lossy().to_string())
+}
+
 #[tauri::command]
 pub async fn cleanup_audio_file(file_path: String) -> Result<(), String> {
     if std::path::Path::new(&file_path).exists() {
@@ -32,10 +109,7 @@ pub async fn cleanup_audio_file(file_path: String) -> Result<(), String> {
 
 #[tauri::command]
 pub async fn list_audio_files(app: AppHandle) -> Result<Vec<String>, String> {
-    let app_data_dir = app.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
-    
-    let audio_dir = app_data_dir.join("audio_cache");
+    let audio_dir = crate::paths::audio_cache_dir(&app)?;
     
     if !audio_dir.exists() {
         return Ok(Vec::new());
@@ -53,4 +127,852 @@ pub async fn list_audio_files(app: AppHandle) -> Result<Vec<String>, String> {
         .collect();
     
     Ok(files)
-}
\ No newline at end of file
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AudioProbe {
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Unknown for compressed formats that have no fixed bit depth
+    pub bits_per_sample: Option<u16>,
+    pub duration_secs: Option<f64>,
+    /// "wav" for WAV files, otherwise the codec short name (e.g. "mp3", "flac", "aac")
+    pub format: String,
+}
+
+/// Inspect an audio file's properties without decoding it, so the UI can warn before transcribing
+/// WAV is read with hound; other formats go through Symphonia's probe.
+#[tauri::command]
+pub async fn probe_audio(path: String) -> Result<AudioProbe, String> {
+    let file_path = std::path::Path::new(&path);
+    if !file_path.exists() {
+        return Err(format!("Audio file not found: {}", path));
+    }
+
+    if let Ok(reader) = hound::WavReader::open(file_path) {
+        let spec = reader.spec();
+        return Ok(AudioProbe {
+            sample_rate: spec.sample_rate,
+            channels: spec.channels,
+            bits_per_sample: Some(spec.bits_per_sample),
+            duration_secs: Some(reader.duration() as f64 / spec.sample_rate.max(1) as f64),
+            format: "wav".to_string(),
+        });
+    }
+
+    probe_with_symphonia(file_path)
+}
+
+/// Open a compressed (or any Symphonia-supported) audio file and detect its container format
+fn open_with_symphonia(
+    path: &std::path::Path,
+) -> Result<symphonia::core::probe::ProbeResult, String> {
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open audio file: {}", e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Unreadable or unsupported audio file: {}", e))
+}
+
+fn probe_with_symphonia(path: &std::path::Path) -> Result<AudioProbe, String> {
+    let probed = open_with_symphonia(path)?;
+
+    let track = probed
+        .format
+        .default_track()
+        .ok_or("Audio file contains no audio track")?;
+    let params = &track.codec_params;
+
+    let sample_rate = params
+        .sample_rate
+        .ok_or("Audio file does not declare a sample rate")?;
+    let channels = params.channels.map(|c| c.count() as u16).unwrap_or(0);
+    let duration_secs = params
+        .n_frames
+        .map(|frames| frames as f64 / sample_rate.max(1) as f64);
+    let format = symphonia::default::get_codecs()
+        .get_codec(params.codec)
+        .map(|codec| codec.short_name.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    Ok(AudioProbe {
+        sample_rate,
+        channels,
+        bits_per_sample: params.bits_per_sample.map(|bits| bits as u16),
+        duration_secs,
+        format,
+    })
+}
+
+/// Rate Whisper expects its input at
+const WHISPER_SAMPLE_RATE: u32 = 16000;
+
+/// Convert any supported audio file to a 16kHz mono 16-bit WAV (what `transcribe_audio`
+/// expects) and return the output path
+#[tauri::command]
+pub async fn convert_to_16khz_wav(input_path: String, output_path: String) -> Result<String, String> {
+    if !std::path::Path::new(&input_path).exists() {
+        return Err(format!("Audio file not found: {}", input_path));
+    }
+
+    let (samples, sample_rate) = read_audio_mono(&input_path)?;
+    if samples.is_empty() {
+        return Err("Audio file contains no samples".to_string());
+    }
+
+    let resampled = resample_audio(&samples, sample_rate, WHISPER_SAMPLE_RATE);
+    write_wav_file(&output_path, &resampled, WHISPER_SAMPLE_RATE, WavBitDepth::Int16)
+        .map_err(|e| format!("Failed to write WAV file: {}", e))?;
+
+    log::info!(
+        "Converted {} ({}Hz) to 16kHz mono WAV at {}",
+        input_path,
+        sample_rate,
+        output_path
+    );
+    Ok(output_path)
+}
+
+/// How far (10ms) from a nominal chunk boundary to look for a zero crossing
+const SPLIT_SEARCH_SECS: f64 = 0.01;
+
+/// Index near `target` where the waveform crosses zero (so the cut doesn't click), or
+/// `target` itself if there is no crossing within `radius` samples
+fn nearest_zero_crossing(samples: &[f32], target: usize, radius: usize) -> usize {
+    let start = target.saturating_sub(radius).max(1);
+    let end = (target + radius).min(samples.len().saturating_sub(1));
+    (start..=end)
+        .filter(|&i| (samples[i - 1] >= 0.0) != (samples[i] >= 0.0))
+        .min_by_key(|&i| i.abs_diff(target))
+        .unwrap_or(target)
+}
+
+/// Split a long recording (any supported format) into sequential mono WAV files of about
+/// `chunk_secs` each, written to `output_dir` as `{name}_part001.wav`, ... Cuts are moved to
+/// the nearest zero crossing to avoid clicks. Returns the chunk paths in order.
+#[tauri::command]
+pub async fn split_audio(
+    input_path: String,
+    chunk_secs: f64,
+    output_dir: String,
+) -> Result<Vec<String>, String> {
+    if !chunk_secs.is_finite() || chunk_secs <= 0.0 {
+        return Err(format!("chunk_secs must be greater than zero (got {})", chunk_secs));
+    }
+    let source = std::path::Path::new(&input_path);
+    if !source.exists() {
+        return Err(format!("Audio file not found: {}", input_path));
+    }
+
+    let (samples, sample_rate) = read_audio_mono(&input_path)?;
+    if samples.is_empty() {
+        return Err("Audio file contains no samples".to_string());
+    }
+
+    let chunk_len = ((chunk_secs * sample_rate as f64).round() as usize).max(1);
+    let radius = (SPLIT_SEARCH_SECS * sample_rate as f64) as usize;
+    let stem = source
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "audio".to_string());
+
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+
+    let mut paths = Vec::new();
+    let mut start = 0;
+    while start < samples.len() {
+        let nominal_end = start + chunk_len;
+        let end = if nominal_end >= samples.len() {
+            samples.len()
+        } else {
+            nearest_zero_crossing(&samples, nominal_end, radius).max(start + 1)
+        };
+
+        let path = std::path::Path::new(&output_dir)
+            .join(format!("{}_part{:03}.wav", stem, paths.len() + 1))
+            .to_string_lossy()
+            .to_string();
+        write_wav_file(&path, &samples[start..end], sample_rate, WavBitDepth::Int16)
+            .map_err(|e| format!("Failed to write WAV file: {}", e))?;
+        paths.push(path);
+        start = end;
+    }
+
+    log::info!("Split {} into {} chunks of ~{}s", input_path, paths.len(), chunk_secs);
+    Ok(paths)
+}
+
+/// Cut a WAV recording down to `[start_secs, end_secs)` and write it as a new file (same format
+/// and channel layout), returning the new path. `end_secs` defaults to the end of the clip.
+#[tauri::command]
+pub async fn trim_audio(
+    path: String,
+    start_secs: f64,
+    end_secs: Option<f64>,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let mut reader = hound::WavReader::open(&path)
+        .map_err(|e| format!("Failed to open WAV: {}", e))?;
+    let spec = reader.spec();
+    let total_frames = reader.duration() as u64;
+    let duration_secs = total_frames as f64 / spec.sample_rate as f64;
+
+    let end_secs = end_secs.unwrap_or(duration_secs);
+    if !(start_secs >= 0.0) || !(end_secs > start_secs) {
+        return Err(format!(
+            "Invalid trim range {:.2}s-{:.2}s: start must be >= 0 and before end",
+            start_secs, end_secs
+        ));
+    }
+    if end_secs > duration_secs + 1e-6 {
+        return Err(format!(
+            "Trim end {:.2}s is past the end of the clip ({:.2}s)",
+            end_secs, duration_secs
+        ));
+    }
+
+    let start_frame = (start_secs * spec.sample_rate as f64).round() as u64;
+    let end_frame = ((end_secs * spec.sample_rate as f64).round() as u64).min(total_frames);
+    let channels = spec.channels as usize;
+    let skip = start_frame as usize * channels;
+    let take = (end_frame - start_frame) as usize * channels;
+
+    let output_path = output_path.unwrap_or_else(|| {
+        let source = std::path::Path::new(&path);
+        let stem = source
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "audio".to_string());
+        source
+            .with_file_name(format!("{}_trimmed_{:.0}-{:.0}.wav", stem, start_secs, end_secs))
+            .to_string_lossy()
+            .to_string()
+    });
+
+    let mut writer = hound::WavWriter::create(&output_path, spec)
+        .map_err(|e| format!("Failed to create WAV file: {}", e))?;
+
+    match spec.sample_format {
+        hound::SampleFormat::Float => {
+            for sample in reader.samples::<f32>().skip(skip).take(take) {
+                let sample = sample.map_err(|e| format!("Failed to read samples: {}", e))?;
+                writer
+                    .write_sample(sample)
+                    .map_err(|e| format!("Failed to write WAV sample: {}", e))?;
+            }
+        }
+        hound::SampleFormat::Int => {
+            for sample in reader.samples::<i32>().skip(skip).take(take) {
+                let sample = sample.map_err(|e| format!("Failed to read samples: {}", e))?;
+                writer
+                    .write_sample(sample)
+                    .map_err(|e| format!("Failed to write WAV sample: {}", e))?;
+            }
+        }
+    }
+
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to finalize WAV file: {}", e))?;
+
+    Ok(output_path)
+}
+
+/// Read a WAV file into mono f32 samples, returning them with the file's sample rate
+/// Supports integer (8-32 bit) and float WAVs with any channel count (downmixed by averaging)
+pub(crate) fn read_wav_mono(path: &str) -> Result<(Vec<f32>, u32), String> {
+    let reader = hound::WavReader::open(path)
+        .map_err(|e| format!("Failed to open WAV: {}", e))?;
+    wav_to_mono(reader)
+}
+
+/// `read_wav_mono` for a WAV that is already in memory (e.g. decoded from base64)
+pub(crate) fn read_wav_mono_bytes(bytes: &[u8]) -> Result<(Vec<f32>, u32), String> {
+    let reader = hound::WavReader::new(std::io::Cursor::new(bytes))
+        .map_err(|e| format!("Failed to parse WAV: {}", e))?;
+    wav_to_mono(reader)
+}
+
+fn wav_to_mono<R: std::io::Read>(mut reader: hound::WavReader<R>) -> Result<(Vec<f32>, u32), String> {
+    let spec = reader.spec();
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read float samples: {}", e))?,
+        hound::SampleFormat::Int => {
+            if spec.bits_per_sample == 0 || spec.bits_per_sample > 32 {
+                return Err(format!("Unsupported bit depth: {}", spec.bits_per_sample));
+            }
+            let max_val = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to read int samples: {}", e))?
+                .into_iter()
+                .map(|s| s as f32 / max_val)
+                .collect()
+        }
+    };
+
+    let channels = spec.channels.max(1) as usize;
+    let mono = if channels > 1 {
+        samples
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect()
+    } else {
+        samples
+    };
+
+    Ok((mono, spec.sample_rate))
+}
+
+/// Decode any Symphonia-supported file (MP3, FLAC, AAC, OGG, ...) into mono f32 samples,
+/// returning them with the track's sample rate
+fn decode_with_symphonia(path: &std::path::Path) -> Result<(Vec<f32>, u32), String> {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::DecoderOptions;
+    use symphonia::core::errors::Error as SymphoniaError;
+
+    let mut reader = open_with_symphonia(path)?.format;
+    let track = reader
+        .default_track()
+        .ok_or("Audio file contains no audio track")?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or("Audio file does not declare a sample rate")?;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported audio codec: {}", e))?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match reader.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(format!("Failed to read audio: {}", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                let channels = spec.channels.count().max(1);
+                let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+                buffer.copy_interleaved_ref(decoded);
+                samples.extend(
+                    buffer
+                        .samples()
+                        .chunks(channels)
+                        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32),
+                );
+            }
+            // A corrupt packet only loses that packet's audio
+            Err(SymphoniaError::DecodeError(e)) => {
+                log::warn!("Skipping undecodable audio packet: {}", e);
+            }
+            Err(e) => return Err(format!("Failed to decode audio: {}", e)),
+        }
+    }
+
+    Ok((samples, sample_rate))
+}
+
+/// Read any supported audio file into mono f32 samples with its sample rate
+/// WAV goes through hound; everything else is decoded with Symphonia.
+pub(crate) fn read_audio_mono(path: &str) -> Result<(Vec<f32>, u32), String> {
+    if hound::WavReader::open(path).is_ok() {
+        return read_wav_mono(path);
+    }
+    decode_with_symphonia(std::path::Path::new(path))
+}
+
+/// Sample encoding for saved WAV files
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum WavBitDepth {
+    /// 16-bit integer PCM: half the size, plenty for speech
+    #[default]
+    Int16,
+    /// 32-bit float: keeps the captured samples exactly, for archiving
+    Float32,
+}
+
+impl WavBitDepth {
+    /// Parse a requested bit depth (16 or 32); None means 16-bit
+    pub(crate) fn from_bits(bits: Option<u16>) -> Result<Self, String> {
+        match bits {
+            None | Some(16) => Ok(WavBitDepth::Int16),
+            Some(32) => Ok(WavBitDepth::Float32),
+            Some(other) => Err(format!(
+                "Unsupported bit depth: {} (expected 16 or 32)",
+                other
+            )),
+        }
+    }
+}
+
+/// Write mono f32 samples to a WAV file at the given sample rate and bit depth
+pub(crate) fn write_wav_file(
+    path: &str,
+    samples: &[f32],
+    sample_rate: u32,
+    bit_depth: WavBitDepth,
+) -> anyhow::Result<()> {
+    if let Some(parent) = std::path::Path::new(path).parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    
+    let (bits_per_sample, sample_format) = match bit_depth {
+        WavBitDepth::Int16 => (16, hound::SampleFormat::Int),
+        WavBitDepth::Float32 => (32, hound::SampleFormat::Float),
+    };
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample,
+        sample_format,
+    };
+    
+    let mut writer = hound::WavWriter::create(path, spec)?;
+    for &sample in samples {
+        let clamped = sample.clamp(-1.0, 1.0);
+        match bit_depth {
+            WavBitDepth::Int16 => writer.write_sample((clamped * i16::MAX as f32) as i16)?,
+            WavBitDepth::Float32 => writer.write_sample(clamped)?,
+        }
+    }
+    writer.finalize()?;
+    
+    Ok(())
+}
+
+/// Peak level below which a live chunk is treated as silence
+pub(crate) const SILENCE_PEAK_THRESHOLD: f32 = 0.01;
+
+/// Largest absolute sample value
+pub(crate) fn peak_amplitude(input: &[f32]) -> f32 {
+    input.iter().map(|&x| x.abs()).fold(0.0f32, f32::max)
+}
+
+/// Peak level audio is normalized to before transcription (leaves headroom)
+pub(crate) const DEFAULT_NORMALIZE_PEAK: f32 = 0.8;
+
+/// Resolve a configured normalization peak, rejecting values outside (0, 1]
+pub(crate) fn normalize_peak(target_peak: Option<f32>) -> Result<f32, String> {
+    let target_peak = target_peak.unwrap_or(DEFAULT_NORMALIZE_PEAK);
+    if !target_peak.is_finite() || target_peak <= 0.0 || target_peak > 1.0 {
+        return Err(format!(
+            "normalize_peak must be greater than 0 and at most 1 (got {})",
+            target_peak
+        ));
+    }
+    Ok(target_peak)
+}
+
+/// Normalize audio to `target_peak` to improve transcription quality
+pub(crate) fn normalize_audio(input: &[f32], target_peak: f32) -> Vec<f32> {
+    if input.is_empty() {
+        return Vec::new();
+    }
+
+    let max_val = peak_amplitude(input);
+
+    if max_val < 1e-6 {
+        // Audio is too quiet
+        return input.to_vec();
+    }
+
+    let scale = target_peak / max_val;
+
+    input
+        .iter()
+        .map(|&x| (x * scale).clamp(-1.0, 1.0))
+        .collect()
+}
+
+// Spectral subtraction settings (frames are at 16kHz, so 512 samples = 32ms)
+const DENOISE_FRAME_LEN: usize = 512;
+const DENOISE_HOP_LEN: usize = DENOISE_FRAME_LEN / 2;
+/// Share of the quietest frames averaged into the noise estimate
+const NOISE_FRAME_FRACTION: f32 = 0.1;
+/// How many times the noise estimate is subtracted from each bin
+const OVER_SUBTRACTION: f32 = 2.0;
+/// Fraction of the original magnitude always kept, to avoid "musical noise" artifacts
+const SPECTRAL_FLOOR: f32 = 0.02;
+
+/// Reduce stationary background noise (fans, café hum) with spectral subtraction.
+/// The noise spectrum is estimated from the quietest frames of the input itself,
+/// so chunks should be long enough to contain some pauses. Output has the same length.
+pub(crate) fn denoise_audio(input: &[f32]) -> Vec<f32> {
+    if input.len() < DENOISE_FRAME_LEN * 2 {
+        return input.to_vec();
+    }
+
+    let mut planner = FftPlanner::<f32>::new();
+    let fft = planner.plan_fft_forward(DENOISE_FRAME_LEN);
+    let ifft = planner.plan_fft_inverse(DENOISE_FRAME_LEN);
+
+    // Periodic Hann window: at 50% overlap the windows sum to 1
+    let window: Vec<f32> = (0..DENOISE_FRAME_LEN)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / DENOISE_FRAME_LEN as f32).cos())
+        .collect();
+
+    // Pad so every input sample is covered by two overlapping frames
+    let mut padded = vec![0.0f32; DENOISE_HOP_LEN];
+    padded.extend_from_slice(input);
+    padded.resize(padded.len() + DENOISE_FRAME_LEN, 0.0);
+
+    let mut spectra: Vec<Vec<Complex<f32>>> = padded
+        .windows(DENOISE_FRAME_LEN)
+        .step_by(DENOISE_HOP_LEN)
+        .map(|frame| {
+            let mut bins: Vec<Complex<f32>> = frame
+                .iter()
+                .zip(&window)
+                .map(|(&x, &w)| Complex::new(x * w, 0.0))
+                .collect();
+            fft.process(&mut bins);
+            bins
+        })
+        .collect();
+
+    // Noise floor = average magnitude spectrum of the quietest frames
+    let mut by_energy: Vec<(usize, f32)> = spectra
+        .iter()
+        .enumerate()
+        .map(|(i, bins)| (i, bins.iter().map(|c| c.norm_sqr()).sum::<f32>()))
+        .collect();
+    by_energy.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let noise_frames = ((spectra.len() as f32 * NOISE_FRAME_FRACTION) as usize).max(1);
+
+    let mut noise = vec![0.0f32; DENOISE_FRAME_LEN];
+    for &(i, _) in &by_energy[..noise_frames] {
+        for (n, c) in noise.iter_mut().zip(&spectra[i]) {
+            *n += c.norm();
+        }
+    }
+    for n in noise.iter_mut() {
+        *n /= noise_frames as f32;
+    }
+
+    // Subtract the noise magnitude, keep the phase, and overlap-add the frames back
+    let mut output = vec![0.0f32; padded.len()];
+    let mut weight = vec![0.0f32; padded.len()];
+    for (frame_index, bins) in spectra.iter_mut().enumerate() {
+        for (c, &n) in bins.iter_mut().zip(&noise) {
+            let magnitude = c.norm();
+            if magnitude > 0.0 {
+                let cleaned = (magnitude - OVER_SUBTRACTION * n).max(SPECTRAL_FLOOR * magnitude);
+                *c *= cleaned / magnitude;
+            }
+        }
+        ifft.process(bins);
+
+        let start = frame_index * DENOISE_HOP_LEN;
+        for (i, c) in bins.iter().enumerate() {
+            output[start + i] += c.re / DENOISE_FRAME_LEN as f32;
+            weight[start + i] += window[i];
+        }
+    }
+
+    output[DENOISE_HOP_LEN..DENOISE_HOP_LEN + input.len()]
+        .iter()
+        .zip(&weight[DENOISE_HOP_LEN..])
+        .map(|(&x, &w)| if w > 1e-3 { x / w } else { x })
+        .collect()
+}
+
+/// Resample audio from one sample rate to another using linear interpolation
+pub(crate) fn resample_audio(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate {
+        return input.to_vec();
+    }
+
+    let ratio = to_rate as f64 / from_rate as f64;
+    let output_len = (input.len() as f64 * ratio) as usize;
+    let mut output = Vec::with_capacity(output_len);
+
+    for i in 0..output_len {
+        let src_pos = i as f64 / ratio;
+        let src_idx = src_pos as usize;
+        let frac = src_pos - src_idx as f64;
+
+        if src_idx + 1 < input.len() {
+            // Linear interpolation
+            let sample = input[src_idx] as f64 * (1.0 - frac) + input[src_idx + 1] as f64 * frac;
+            output.push(sample as f32);
+        } else if src_idx < input.len() {
+            output.push(input[src_idx]);
+        }
+    }
+
+    output
+}
+
+/// Strip leading/trailing low-energy audio, keeping a short pad around the speech
+/// Returns the number of samples removed from the start along with the trimmed slice.
+/// If nothing exceeds the threshold the clip is returned untouched.
+pub(crate) fn trim_silence(samples: &[f32], sample_rate: u32, threshold: f32) -> (usize, &[f32]) {
+    const PAD_SECS: f32 = 0.2;
+
+    let first = samples.iter().position(|s| s.abs() > threshold);
+    let last = samples.iter().rposition(|s| s.abs() > threshold);
+
+    match (first, last) {
+        (Some(first), Some(last)) => {
+            let pad = (sample_rate as f32 * PAD_SECS) as usize;
+            let start = first.saturating_sub(pad);
+            let end = (last + 1 + pad).min(samples.len());
+            (start, &samples[start..end])
+        }
+        // Whole clip is quiet - leave it to Whisper rather than sending nothing
+        _ => (0, samples),
+    }
+}
+
+/// Level reported for digital silence instead of -inf
+const SILENCE_FLOOR_DB: f32 = -120.0;
+/// Samples at or above this magnitude count as clipped
+const CLIPPING_THRESHOLD: f32 = 0.99;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AudioLevels {
+    /// RMS level in dBFS
+    pub rms_db: f32,
+    /// Peak level in dBFS
+    pub peak_db: f32,
+    /// Fraction of samples at or near full scale (0.0 - 1.0)
+    pub clipping_ratio: f32,
+}
+
+fn amplitude_to_db(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        SILENCE_FLOOR_DB
+    } else {
+        (20.0 * amplitude.log10()).max(SILENCE_FLOOR_DB)
+    }
+}
+
+/// One-shot level analysis of a captured clip (either raw samples or a WAV path) so the UI can
+/// warn about clipping or input that's too quiet before recording for real
+#[tauri::command]
+pub async fn analyze_audio_levels(
+    path: Option<String>,
+    samples: Option<Vec<f32>>,
+) -> Result<AudioLevels, String> {
+    let samples = match (samples, path) {
+        (Some(samples), _) => samples,
+        (None, Some(path)) => read_wav_mono(&path)?.0,
+        (None, None) => return Err("Either samples or path must be provided".to_string()),
+    };
+    if samples.is_empty() {
+        return Err("Audio is empty".to_string());
+    }
+
+    let mean_square = samples.iter().map(|&x| (x as f64) * (x as f64)).sum::<f64>()
+        / samples.len() as f64;
+    let clipped = samples.iter().filter(|&&x| x.abs() >= CLIPPING_THRESHOLD).count();
+
+    Ok(AudioLevels {
+        rms_db: amplitude_to_db(mean_square.sqrt() as f32),
+        peak_db: amplitude_to_db(peak_amplitude(&samples)),
+        clipping_ratio: clipped as f32 / samples.len() as f32,
+    })
+}
+
+// Speech/music heuristic settings (frames are at 16kHz, so 512 samples = 32ms)
+const CLASSIFY_SAMPLE_RATE: u32 = 16000;
+const CLASSIFY_FRAME_LEN: usize = 512;
+const CLASSIFY_HOP_LEN: usize = CLASSIFY_FRAME_LEN / 2;
+/// Frames quieter than this RMS are ignored when measuring the spectrum
+const CLASSIFY_MIN_FRAME_RMS: f32 = 0.002;
+/// Above this mean spectral flatness the audio is treated as noise
+const MAX_SPEECH_FLATNESS: f32 = 0.45;
+/// Speech pauses between syllables, so a good share of its frames sit well below the mean energy
+const MIN_SPEECH_LOW_ENERGY_RATIO: f32 = 0.2;
+/// Speech alternates voiced and unvoiced sounds, so some frames have a much higher ZCR than average
+const MIN_SPEECH_HIGH_ZCR_RATIO: f32 = 0.1;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SpeechClassification {
+    pub is_speech: bool,
+    /// Mean spectral flatness of the audible frames (0 = tonal, 1 = white noise)
+    pub spectral_flatness: f32,
+    /// Fraction of frames below half the mean frame energy
+    pub low_energy_ratio: f32,
+    /// Fraction of frames whose zero-crossing rate exceeds 1.5x the mean
+    pub high_zcr_ratio: f32,
+}
+
+/// Cheap speech vs music/noise heuristic for 16kHz mono audio.
+/// Noise has a flat spectrum; music is flatter in energy and zero-crossing rate over time
+/// than speech, which keeps dipping between syllables and switching between voiced and
+/// unvoiced sounds. Too little audio to judge counts as speech so nothing is dropped.
+pub(crate) fn classify_speech(samples: &[f32]) -> SpeechClassification {
+    let unknown = SpeechClassification {
+        is_speech: true,
+        spectral_flatness: 0.0,
+        low_energy_ratio: 0.0,
+        high_zcr_ratio: 0.0,
+    };
+    if samples.len() < CLASSIFY_FRAME_LEN * 4 {
+        return unknown;
+    }
+
+    let mut planner = FftPlanner::<f32>::new();
+    let fft = planner.plan_fft_forward(CLASSIFY_FRAME_LEN);
+    let window: Vec<f32> = (0..CLASSIFY_FRAME_LEN)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / CLASSIFY_FRAME_LEN as f32).cos())
+        .collect();
+    // Only the 100Hz - 4kHz band, where speech energy lives
+    let bin_hz = CLASSIFY_SAMPLE_RATE as f32 / CLASSIFY_FRAME_LEN as f32;
+    let bands = (100.0 / bin_hz) as usize..(4000.0 / bin_hz) as usize;
+
+    let mut energies = Vec::new();
+    let mut zcrs = Vec::new();
+    let mut flatness_sum = 0.0f32;
+    let mut audible_frames = 0usize;
+
+    for frame in samples.windows(CLASSIFY_FRAME_LEN).step_by(CLASSIFY_HOP_LEN) {
+        let energy = frame.iter().map(|&x| x * x).sum::<f32>() / frame.len() as f32;
+        let crossings = frame
+            .windows(2)
+            .filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0))
+            .count();
+        energies.push(energy);
+        zcrs.push(crossings as f32 / frame.len() as f32);
+
+        if energy.sqrt() < CLASSIFY_MIN_FRAME_RMS {
+            continue;
+        }
+        let mut bins: Vec<Complex<f32>> = frame
+            .iter()
+            .zip(&window)
+            .map(|(&x, &w)| Complex::new(x * w, 0.0))
+            .collect();
+        fft.process(&mut bins);
+        let power: Vec<f32> = bins[bands.clone()].iter().map(|c| c.norm_sqr() + 1e-12).collect();
+        let log_mean = power.iter().map(|p| p.ln()).sum::<f32>() / power.len() as f32;
+        let mean = power.iter().sum::<f32>() / power.len() as f32;
+        flatness_sum += log_mean.exp() / mean;
+        audible_frames += 1;
+    }
+
+    if audible_frames < 4 {
+        return unknown;
+    }
+
+    let mean_energy = energies.iter().sum::<f32>() / energies.len() as f32;
+    let mean_zcr = zcrs.iter().sum::<f32>() / zcrs.len() as f32;
+    let low_energy_ratio =
+        energies.iter().filter(|&&e| e < 0.5 * mean_energy).count() as f32 / energies.len() as f32;
+    let high_zcr_ratio =
+        zcrs.iter().filter(|&&z| z > 1.5 * mean_zcr).count() as f32 / zcrs.len() as f32;
+    let spectral_flatness = flatness_sum / audible_frames as f32;
+
+    SpeechClassification {
+        is_speech: spectral_flatness <= MAX_SPEECH_FLATNESS
+            && (low_energy_ratio >= MIN_SPEECH_LOW_ENERGY_RATIO
+                || high_zcr_ratio >= MIN_SPEECH_HIGH_ZCR_RATIO),
+        spectral_flatness,
+        low_energy_ratio,
+        high_zcr_ratio,
+    }
+}
+
+/// Classify a clip (raw samples or a WAV path) as speech or music/noise with the same
+/// heuristic `speech_only` transcription uses. Raw samples default to 16kHz.
+#[tauri::command]
+pub async fn classify_audio(
+    path: Option<String>,
+    samples: Option<Vec<f32>>,
+    sample_rate: Option<u32>,
+) -> Result<SpeechClassification, String> {
+    let (samples, rate) = match (samples, path) {
+        (Some(samples), _) => (samples, sample_rate.unwrap_or(CLASSIFY_SAMPLE_RATE)),
+        (None, Some(path)) => read_wav_mono(&path)?,
+        (None, None) => return Err("Either samples or path must be provided".to_string()),
+    };
+    if samples.is_empty() {
+        return Err("Audio is empty".to_string());
+    }
+    if rate == 0 {
+        return Err("sample_rate must be greater than zero".to_string());
+    }
+
+    Ok(classify_speech(&resample_audio(&samples, rate, CLASSIFY_SAMPLE_RATE)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Signal-to-noise ratio of `signal` against the known clean version, in dB
+    fn snr_db(clean: &[f32], signal: &[f32]) -> f32 {
+        let power: f32 = clean.iter().map(|s| s * s).sum();
+        let noise: f32 = clean.iter().zip(signal).map(|(c, s)| (s - c) * (s - c)).sum();
+        10.0 * (power / noise).log10()
+    }
+
+    #[test]
+    fn denoise_audio_improves_snr_of_noisy_tone() {
+        let sample_rate = 16_000;
+        // Two 440Hz bursts with pauses between them, so there are noise-only frames to learn from
+        let clean: Vec<f32> = (0..sample_rate * 2)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                let voiced = (0.25..0.75).contains(&t) || (1.0..1.75).contains(&t);
+                if voiced {
+                    0.5 * (2.0 * std::f32::consts::PI * 440.0 * t).sin()
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        // Deterministic white noise in [-0.1, 0.1] from a linear congruential generator
+        let mut seed: u32 = 12345;
+        let noisy: Vec<f32> = clean
+            .iter()
+            .map(|s| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                s + (seed >> 8) as f32 / (1u32 << 24) as f32 * 0.2 - 0.1
+            })
+            .collect();
+
+        let denoised = denoise_audio(&noisy);
+        assert_eq!(denoised.len(), noisy.len());
+
+        let before = snr_db(&clean, &noisy);
+        let after = snr_db(&clean, &denoised);
+        assert!(after > before + 3.0, "SNR {:.1}dB -> {:.1}dB", before, after);
+    }
+
+    #[test]
+    fn denoise_audio_leaves_short_input_untouched() {
+        let input = vec![0.25f32; DENOISE_FRAME_LEN];
+        assert_eq!(denoise_audio(&input), input);
+    }
+}
@@ -0,0 +1,63 @@
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::settings;
+
+/// Argument passed to the app when it is launched at login
+pub const AUTOSTART_ARG: &str = "--autostarted";
+
+/// Settings key recording the user's "launch at login" choice
+const AUTOSTART_SETTING: &str = "autostart_enabled";
+
+/// Enable or disable launching the app at login and remember the choice
+#[tauri::command]
+pub fn set_autostart<R: Runtime>(app: AppHandle<R>, enabled: bool) -> Result<bool, String> {
+    let autolaunch = app.autolaunch();
+    if enabled {
+        autolaunch
+            .enable()
+            .map_err(|e| format!("Failed to enable autostart: {}", e))?;
+    } else {
+        autolaunch
+            .disable()
+            .map_err(|e| format!("Failed to disable autostart: {}", e))?;
+    }
+
+    settings::set_value(&app, AUTOSTART_SETTING, serde_json::Value::Bool(enabled))?;
+
+    autolaunch
+        .is_enabled()
+        .map_err(|e| format!("Failed to check autostart status: {}", e))
+}
+
+/// Whether the app is currently registered to launch at login
+#[tauri::command]
+pub fn is_autostart_enabled<R: Runtime>(app: AppHandle<R>) -> Result<bool, String> {
+    app.autolaunch()
+        .is_enabled()
+        .map_err(|e| format!("Failed to check autostart status: {}", e))
+}
+
+/// Whether this process was started by the OS at login (e.g. to start minimized)
+#[tauri::command]
+pub fn was_autostarted() -> bool {
+    std::env::args().any(|arg| arg == AUTOSTART_ARG)
+}
+
+/// Re-apply the saved preference on startup, in case the OS entry was removed or is stale
+pub fn sync_autostart_preference<R: Runtime>(app: &AppHandle<R>) {
+    let Some(enabled) = settings::get_bool(app, AUTOSTART_SETTING) else {
+        return;
+    };
+
+    let autolaunch = app.autolaunch();
+    let result = match (enabled, autolaunch.is_enabled().unwrap_or(!enabled)) {
+        (true, false) => autolaunch.enable(),
+        (false, true) => autolaunch.disable(),
+        _ => Ok(()),
+    };
+
+    if let Err(e) = result {
+        log::warn!("⚠ Failed to apply saved autostart preference: {}", e);
+    }
+}
@@ -0,0 +1,402 @@
+// Full JSON backup of a user's data. Rows are streamed from the database straight into the
+// file, so large accounts are never held in memory all at once.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, PgPool};
+use tauri::State;
+use uuid::Uuid;
+
+use crate::database::{
+    Chat, Conversation, ConversationMessage, DbState, Message, MessageRole, Summary,
+    Transcription, TranscriptionSegment,
+};
+
+/// Bump when the backup layout changes so a future import can handle old files
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Rows per table written by an export or inserted by an import
+#[derive(Serialize, Debug, Default)]
+pub struct BackupCounts {
+    pub conversations: usize,
+    pub conversation_messages: usize,
+    pub chats: usize,
+    pub messages: usize,
+    pub summaries: usize,
+    pub transcriptions: usize,
+    pub transcription_segments: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExportResult {
+    pub path: String,
+    pub counts: BackupCounts,
+}
+
+/// Write `"name": [rows...]` for every row `sql` returns for the user
+async fn write_section<T>(
+    writer: &mut BufWriter<File>,
+    pool: &PgPool,
+    name: &str,
+    sql: &str,
+    user_id: &str,
+) -> Result<usize, String>
+where
+    T: for<'r> FromRow<'r, PgRow> + Serialize + Send + Unpin,
+{
+    let io_err = |e: std::io::Error| format!("Failed to write backup: {}", e);
+
+    write!(writer, ",\n  \"{}\": [", name).map_err(io_err)?;
+
+    let mut rows = sqlx::query_as::<_, T>(sql).bind(user_id).fetch(pool);
+    let mut count = 0;
+    while let Some(row) = rows
+        .try_next()
+        .await
+        .map_err(|e| format!("Failed to export {}: {}", name, e))?
+    {
+        writer
+            .write_all(if count == 0 { b"\n    " } else { b",\n    " })
+            .map_err(io_err)?;
+        serde_json::to_writer(&mut *writer, &row)
+            .map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+        count += 1;
+    }
+
+    writer
+        .write_all(if count == 0 { b"]" } else { b"\n  ]" })
+        .map_err(io_err)?;
+    Ok(count)
+}
+
+async fn write_backup(
+    writer: &mut BufWriter<File>,
+    pool: &PgPool,
+    user_id: &str,
+) -> Result<BackupCounts, String> {
+    let header = serde_json::json!({
+        "version": BACKUP_FORMAT_VERSION,
+        "exported_at": chrono::Utc::now().to_rfc3339(),
+        "user_id": user_id,
+    })
+    .to_string();
+    // Reopen the header object so the sections can be streamed in after it
+    write!(writer, "{{\n  {}", header.trim_start_matches('{').trim_end_matches('}'))
+        .map_err(|e| format!("Failed to write backup: {}", e))?;
+
+    let mut counts = BackupCounts::default();
+
+    counts.conversations = write_section::<Conversation>(
+        writer,
+        pool,
+        "conversations",
+        "SELECT id, user_id, title, type, created_at, updated_at FROM conversations WHERE user_id = $1 ORDER BY created_at ASC",
+        user_id,
+    )
+    .await?;
+    counts.conversation_messages = write_section::<ConversationMessage>(
+        writer,
+        pool,
+        "conversation_messages",
+        "SELECT id, conversation_id, user_id, role, content, created_at FROM conversation_messages WHERE user_id = $1 ORDER BY created_at ASC",
+        user_id,
+    )
+    .await?;
+    counts.chats = write_section::<Chat>(
+        writer,
+        pool,
+        "chats",
+        "SELECT id, conversation_id, user_id, title, created_at, updated_at FROM chats WHERE user_id = $1 ORDER BY created_at ASC",
+        user_id,
+    )
+    .await?;
+    counts.messages = write_section::<Message>(
+        writer,
+        pool,
+        "messages",
+        "SELECT m.id, m.chat_id, m.role, m.content, m.created_at FROM messages m INNER JOIN chats c ON m.chat_id = c.id WHERE c.user_id = $1 ORDER BY m.created_at ASC",
+        user_id,
+    )
+    .await?;
+    counts.summaries = write_section::<Summary>(
+        writer,
+        pool,
+        "summaries",
+        "SELECT id, conversation_id, user_id, title, content, created_at, updated_at FROM summaries WHERE user_id = $1 ORDER BY created_at ASC",
+        user_id,
+    )
+    .await?;
+    counts.transcriptions = write_section::<Transcription>(
+        writer,
+        pool,
+        "transcriptions",
+        "SELECT id, conversation_id, user_id, title, created_at, updated_at FROM transcriptions WHERE user_id = $1 ORDER BY created_at ASC",
+        user_id,
+    )
+    .await?;
+    counts.transcription_segments = write_section::<TranscriptionSegment>(
+        writer,
+        pool,
+        "transcription_segments",
+        "SELECT ts.id, ts.transcription_id, ts.text, ts.start_time, ts.end_time, ts.created_at FROM transcription_segments ts INNER JOIN transcriptions t ON ts.transcription_id = t.id WHERE t.user_id = $1 ORDER BY ts.created_at ASC",
+        user_id,
+    )
+    .await?;
+
+    writer
+        .write_all(b"\n}\n")
+        .and_then(|_| writer.flush())
+        .map_err(|e| format!("Failed to write backup: {}", e))?;
+
+    Ok(counts)
+}
+
+/// Export all of a user's conversations, chats, messages, summaries, transcriptions and
+/// segments into one versioned JSON file at `path`
+/// The file is written next to the destination first and renamed on success, so a failed
+/// export never leaves a truncated backup at `path`.
+#[tauri::command]
+pub async fn export_database(
+    state: State<'_, DbState>,
+    user_id: String,
+    path: String,
+) -> Result<ExportResult, String> {
+    let target = Path::new(&path);
+    if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    }
+    let temp_path = target.with_extension("json.partial");
+
+    let file = File::create(&temp_path)
+        .map_err(|e| format!("Failed to create backup file: {}", e))?;
+    let mut writer = BufWriter::new(file);
+
+    let pool = state.pool();
+    let counts = match write_backup(&mut writer, &pool, &user_id).await {
+        Ok(counts) => counts,
+        Err(e) => {
+            drop(writer);
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e);
+        }
+    };
+    drop(writer);
+
+    std::fs::rename(&temp_path, target)
+        .map_err(|e| format!("Failed to finalize backup file: {}", e))?;
+
+    log::info!("✓ Exported database backup to {}: {:?}", path, counts);
+    Ok(ExportResult { path, counts })
+}
+
+/// Parsed backup file; sections missing from the file are treated as empty
+#[derive(Deserialize)]
+struct Backup {
+    version: Option<u32>,
+    #[serde(default)]
+    conversations: Vec<Conversation>,
+    #[serde(default)]
+    conversation_messages: Vec<ConversationMessage>,
+    #[serde(default)]
+    chats: Vec<Chat>,
+    #[serde(default)]
+    messages: Vec<Message>,
+    #[serde(default)]
+    summaries: Vec<Summary>,
+    #[serde(default)]
+    transcriptions: Vec<Transcription>,
+    #[serde(default)]
+    transcription_segments: Vec<TranscriptionSegment>,
+}
+
+/// Look up the new id for a row's parent; rows whose parent wasn't in the backup are skipped
+fn remap(ids: &HashMap<Uuid, Uuid>, old: Uuid) -> Option<Uuid> {
+    ids.get(&old).copied()
+}
+
+fn import_error(e: sqlx::Error) -> String {
+    format!("Failed to import backup: {}", e)
+}
+
+/// Backup files can be edited by hand, so roles go through the same allow-list as new messages
+fn backup_role(role: &str) -> Result<MessageRole, String> {
+    MessageRole::parse(role).map_err(|e| format!("Invalid backup file: {}", e))
+}
+
+async fn insert_backup(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    backup: Backup,
+    user_id: &str,
+) -> Result<BackupCounts, String> {
+    let mut counts = BackupCounts::default();
+    let mut conversation_ids = HashMap::new();
+    let mut chat_ids = HashMap::new();
+    let mut transcription_ids = HashMap::new();
+
+    for row in backup.conversations {
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO conversations (user_id, title, type, created_at, updated_at) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+        )
+        .bind(user_id)
+        .bind(&row.title)
+        .bind(&row.r#type)
+        .bind(row.created_at.naive_utc())
+        .bind(row.updated_at.naive_utc())
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(import_error)?;
+        conversation_ids.insert(row.id, id);
+        counts.conversations += 1;
+    }
+
+    for row in backup.conversation_messages {
+        let Some(conversation_id) = remap(&conversation_ids, row.conversation_id) else {
+            continue;
+        };
+        sqlx::query(
+            "INSERT INTO conversation_messages (conversation_id, user_id, role, content, created_at) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(conversation_id)
+        .bind(user_id)
+        .bind(backup_role(&row.role)?.as_str())
+        .bind(&row.content)
+        .bind(row.created_at.naive_utc())
+        .execute(&mut **tx)
+        .await
+        .map_err(import_error)?;
+        counts.conversation_messages += 1;
+    }
+
+    for row in backup.chats {
+        let conversation_id = row.conversation_id.and_then(|id| remap(&conversation_ids, id));
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO chats (conversation_id, user_id, title, created_at, updated_at) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+        )
+        .bind(conversation_id)
+        .bind(user_id)
+        .bind(&row.title)
+        .bind(row.created_at.naive_utc())
+        .bind(row.updated_at.naive_utc())
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(import_error)?;
+        chat_ids.insert(row.id, id);
+        counts.chats += 1;
+    }
+
+    for row in backup.messages {
+        let Some(chat_id) = remap(&chat_ids, row.chat_id) else {
+            continue;
+        };
+        sqlx::query(
+            "INSERT INTO messages (chat_id, role, content, created_at) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(chat_id)
+        .bind(backup_role(&row.role)?.as_str())
+        .bind(&row.content)
+        .bind(row.created_at.naive_utc())
+        .execute(&mut **tx)
+        .await
+        .map_err(import_error)?;
+        counts.messages += 1;
+    }
+
+    for row in backup.summaries {
+        let conversation_id = row.conversation_id.and_then(|id| remap(&conversation_ids, id));
+        sqlx::query(
+            "INSERT INTO summaries (conversation_id, user_id, title, content, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(conversation_id)
+        .bind(user_id)
+        .bind(&row.title)
+        .bind(&row.content)
+        .bind(row.created_at.naive_utc())
+        .bind(row.updated_at.naive_utc())
+        .execute(&mut **tx)
+        .await
+        .map_err(import_error)?;
+        counts.summaries += 1;
+    }
+
+    for row in backup.transcriptions {
+        let conversation_id = row.conversation_id.and_then(|id| remap(&conversation_ids, id));
+        let id: Uuid = sqlx::query_scalar(
+            "INSERT INTO transcriptions (conversation_id, user_id, title, created_at, updated_at) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+        )
+        .bind(conversation_id)
+        .bind(user_id)
+        .bind(&row.title)
+        .bind(row.created_at.naive_utc())
+        .bind(row.updated_at.naive_utc())
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(import_error)?;
+        transcription_ids.insert(row.id, id);
+        counts.transcriptions += 1;
+    }
+
+    for row in backup.transcription_segments {
+        let Some(transcription_id) = remap(&transcription_ids, row.transcription_id) else {
+            continue;
+        };
+        sqlx::query(
+            "INSERT INTO transcription_segments (transcription_id, text, start_time, end_time, created_at) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(transcription_id)
+        .bind(&row.text)
+        .bind(row.start_time.map(|v| v as f32))
+        .bind(row.end_time.map(|v| v as f32))
+        .bind(row.created_at.naive_utc())
+        .execute(&mut **tx)
+        .await
+        .map_err(import_error)?;
+        counts.transcription_segments += 1;
+    }
+
+    Ok(counts)
+}
+
+/// Restore a JSON backup made by `export_database` into `user_id`'s account
+/// Every row gets a fresh id (parent references are remapped), so importing never collides
+/// with existing data; the whole import runs in one transaction and is all-or-nothing.
+#[tauri::command]
+pub async fn import_database(
+    state: State<'_, DbState>,
+    user_id: String,
+    path: String,
+) -> Result<BackupCounts, String> {
+    let file = File::open(&path)
+        .map_err(|e| format!("Failed to open backup file: {}", e))?;
+    let backup: Backup = serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| format!("Invalid backup file: {}", e))?;
+
+    match backup.version {
+        Some(BACKUP_FORMAT_VERSION) => {}
+        Some(version) => {
+            return Err(format!(
+                "Backup format version {} is not supported by this app (expected {})",
+                version, BACKUP_FORMAT_VERSION
+            ))
+        }
+        None => return Err("Invalid backup file: missing format version".to_string()),
+    }
+
+    let pool = state.pool();
+    let mut tx = pool.begin().await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    // Any error drops `tx` uncommitted, which rolls the whole import back
+    let counts = insert_backup(&mut tx, backup, &user_id).await?;
+
+    tx.commit().await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    log::info!("✓ Imported database backup from {}: {:?}", path, counts);
+    Ok(counts)
+}
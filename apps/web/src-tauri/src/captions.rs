@@ -0,0 +1,160 @@
+// Local caption server: broadcasts finalized transcription segments as JSON over WebSocket
+// so external tools (OBS browser sources, caption overlays, ...) can consume them.
+// Off by default - only runs between start_caption_server and stop_caption_server.
+
+use std::net::SocketAddr;
+use std::sync::Mutex;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State as AxumState,
+    },
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use serde::Serialize;
+use tauri::{Manager, Runtime, State};
+use tokio::sync::{broadcast, oneshot};
+
+const DEFAULT_CAPTION_PORT: u16 = 7878;
+/// Segments buffered per client before slow clients start skipping
+const CAPTION_CHANNEL_CAPACITY: usize = 256;
+
+struct CaptionServer {
+    port: u16,
+    sender: broadcast::Sender<String>,
+    shutdown: oneshot::Sender<()>,
+}
+
+#[derive(Default)]
+pub struct CaptionServerState {
+    server: Mutex<Option<CaptionServer>>,
+}
+
+#[derive(Serialize)]
+struct CaptionMessage<'a> {
+    /// "microphone" or "system_audio"
+    source: &'a str,
+    text: &'a str,
+    timestamp: String,
+}
+
+/// Send a finalized segment to every connected caption client (no-op when the server is off)
+pub fn publish_caption<R: Runtime, M: Manager<R>>(manager: &M, source: &str, text: &str) {
+    let Some(state) = manager.try_state::<CaptionServerState>() else {
+        return;
+    };
+    let guard = state.server.lock().unwrap();
+    let Some(server) = guard.as_ref() else {
+        return;
+    };
+    if server.sender.receiver_count() == 0 {
+        return;
+    }
+
+    let message = CaptionMessage {
+        source,
+        text,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+    match serde_json::to_string(&message) {
+        Ok(json) => {
+            let _ = server.sender.send(json);
+        }
+        Err(e) => log::warn!("Failed to serialize caption: {}", e),
+    }
+}
+
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    AxumState(sender): AxumState<broadcast::Sender<String>>,
+) -> impl IntoResponse {
+    let receiver = sender.subscribe();
+    ws.on_upgrade(move |socket| handle_socket(socket, receiver))
+}
+
+async fn handle_socket(mut socket: WebSocket, mut receiver: broadcast::Receiver<String>) {
+    log::info!("Caption client connected");
+    loop {
+        tokio::select! {
+            caption = receiver.recv() => match caption {
+                Ok(json) => {
+                    if socket.send(Message::Text(json.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("Caption client lagged, skipped {} segments", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+        }
+    }
+    log::info!("Caption client disconnected");
+}
+
+/// Start the local caption server on 127.0.0.1; returns the port it's listening on
+#[tauri::command]
+pub async fn start_caption_server(
+    state: State<'_, CaptionServerState>,
+    port: Option<u16>,
+) -> Result<u16, String> {
+    if let Some(server) = state.server.lock().unwrap().as_ref() {
+        return Err(format!("Caption server already running on port {}", server.port));
+    }
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port.unwrap_or(DEFAULT_CAPTION_PORT)));
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("Failed to bind caption server to {}: {}", addr, e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read caption server address: {}", e))?
+        .port();
+
+    let (sender, _) = broadcast::channel(CAPTION_CHANNEL_CAPACITY);
+    let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+    let router = Router::new()
+        .route("/", get(ws_handler))
+        .with_state(sender.clone());
+
+    tauri::async_runtime::spawn(async move {
+        let result = axum::serve(listener, router)
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+        if let Err(e) = result {
+            log::error!("Caption server stopped with error: {}", e);
+        }
+    });
+
+    let mut guard = state.server.lock().unwrap();
+    if guard.is_some() {
+        let _ = shutdown.send(());
+        return Err("Caption server already running".into());
+    }
+    *guard = Some(CaptionServer {
+        port,
+        sender,
+        shutdown,
+    });
+
+    log::info!("Caption server listening on ws://127.0.0.1:{}", port);
+    Ok(port)
+}
+
+#[tauri::command]
+pub async fn stop_caption_server(state: State<'_, CaptionServerState>) -> Result<(), String> {
+    if let Some(server) = state.server.lock().unwrap().take() {
+        let _ = server.shutdown.send(());
+        log::info!("Caption server on port {} stopped", server.port);
+    }
+    Ok(())
+}
@@ -2,8 +2,12 @@ use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgPoolOptions, FromRow, PgPool, Row};
 use std::env;
 use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
 use uuid::Uuid;
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::oneshot;
+
+use crate::settings;
 
 // === Types ===
 
@@ -141,13 +145,67 @@ pub struct CreateMessageInput {
     pub content: String,
 }
 
+/// Roles a stored message may have (persisted as lowercase text)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageRole {
+    User,
+    Assistant,
+    System,
+}
+
+impl MessageRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MessageRole::User => "user",
+            MessageRole::Assistant => "assistant",
+            MessageRole::System => "system",
+        }
+    }
+
+    /// Parse a role string, rejecting anything outside the allow-list
+    pub fn parse(role: &str) -> Result<Self, String> {
+        match role {
+            "user" => Ok(MessageRole::User),
+            "assistant" => Ok(MessageRole::Assistant),
+            "system" => Ok(MessageRole::System),
+            other => Err(format!(
+                "Invalid message role {:?}: expected \"user\", \"assistant\" or \"system\"",
+                other
+            )),
+        }
+    }
+}
+
 // === Database State Management ===
 
 /// Wrapper struct for managing the database pool in Tauri state
+/// The pool sits behind a lock so `set_database_url` can swap it at runtime.
 pub struct DbState {
-    pub pool: PgPool,
+    pool: RwLock<PgPool>,
+}
+
+impl DbState {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool: RwLock::new(pool),
+        }
+    }
+
+    /// Current pool (cheap to clone - it's reference counted)
+    pub fn pool(&self) -> PgPool {
+        self.pool.read().unwrap().clone()
+    }
+
+    /// Replace the pool, returning the previous one so it can be closed
+    fn replace_pool(&self, pool: PgPool) -> PgPool {
+        std::mem::replace(&mut *self.pool.write().unwrap(), pool)
+    }
 }
 
+/// Settings store key holding a user-configured connection string
+const DATABASE_URL_SETTING: &str = "database_url";
+
 /// Initialize dotenv (load .env file)
 /// Tries to load from multiple locations including Tauri resource directory (production)
 fn init_dotenv(app_handle: Option<&AppHandle>) {
@@ -209,17 +267,72 @@ fn init_dotenv(app_handle: Option<&AppHandle>) {
     }
 }
 
+/// Read a positive duration (in seconds) from the environment, falling back to `default`
+fn env_duration_secs(name: &str, default: u64) -> u64 {
+    match env::var(name) {
+        Ok(raw) => match raw.trim().parse::<u64>() {
+            Ok(secs) if secs > 0 => secs,
+            _ => {
+                log::warn!(
+                    "⚠ Ignoring invalid {}={:?} (must be a positive number of seconds), using {}s",
+                    name,
+                    raw,
+                    default
+                );
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
 /// Create and initialize the database connection pool with optimized settings
 pub async fn create_pool(app_handle: Option<&AppHandle>) -> Result<PgPool, String> {
     // Load .env file
     init_dotenv(app_handle);
     
     // Prefer pooler URL for better connection performance (Supabase pooler recommended)
+    // Fall back to a URL saved in the settings store (set in-app via `set_database_url`)
     let database_url = env::var("VITE_DATABASE_URL_POOLER")
         .or_else(|_| env::var("DATABASE_URL_POOLER"))
         .or_else(|_| env::var("VITE_DATABASE_URL"))
         .or_else(|_| env::var("DATABASE_URL"))
-        .map_err(|_| "DATABASE_URL or VITE_DATABASE_URL environment variable not set")?;
+        .ok()
+        .or_else(|| {
+            let url = app_handle.and_then(|app| settings::get_string(app, DATABASE_URL_SETTING));
+            if url.is_some() {
+                log::info!("✓ Using database URL from settings store");
+            }
+            url
+        })
+        .ok_or("DATABASE_URL or VITE_DATABASE_URL environment variable not set, and no database_url saved in settings")?;
+
+    connect_pool(&database_url).await
+}
+
+/// Check that a connection string looks like a usable Postgres URL before connecting
+fn validate_database_url(database_url: &str) -> Result<(), String> {
+    let parsed = url::Url::parse(database_url.trim())
+        .map_err(|e| format!("Invalid database URL: {}", e))?;
+
+    if !matches!(parsed.scheme(), "postgres" | "postgresql") {
+        return Err(format!(
+            "Invalid database URL: expected a postgres:// or postgresql:// URL, got {}://",
+            parsed.scheme()
+        ));
+    }
+    if parsed.host_str().map_or(true, |host| host.is_empty()) {
+        return Err("Invalid database URL: missing host".to_string());
+    }
+
+    Ok(())
+}
+
+/// Connect a new pool to `database_url` with the app's pool settings
+async fn connect_pool(database_url: &str) -> Result<PgPool, String> {
+    // Connection recycling can be tuned per provider (e.g. shorter lifetimes behind PgBouncer)
+    let idle_timeout_secs = env_duration_secs("DB_IDLE_TIMEOUT_SECS", 300);
+    let max_lifetime_secs = env_duration_secs("DB_MAX_LIFETIME_SECS", 1800);
 
     log::info!("🔄 Initializing database connection pool...");
     let start = std::time::Instant::now();
@@ -232,12 +345,12 @@ pub async fn create_pool(app_handle: Option<&AppHandle>) -> Result<PgPool, Strin
     
     // Timeouts
     .acquire_timeout(std::time::Duration::from_secs(30)) // CHANGED: Increased from 3s to 30s
-    .idle_timeout(Some(std::time::Duration::from_secs(300))) 
-    .max_lifetime(Some(std::time::Duration::from_secs(1800))) 
+    .idle_timeout(Some(std::time::Duration::from_secs(idle_timeout_secs)))
+    .max_lifetime(Some(std::time::Duration::from_secs(max_lifetime_secs)))
     
     .test_before_acquire(true)
     
-    .connect(&database_url)
+    .connect(database_url)
     .await
     .map_err(|e| format!("Failed to connect to database: {}", e))?;
     
@@ -245,6 +358,8 @@ pub async fn create_pool(app_handle: Option<&AppHandle>) -> Result<PgPool, Strin
     log::info!("✓ Database pool initialized in {:?}", elapsed);
     log::info!("  • Min connections: 5 (pre-warmed)");
     log::info!("  • Max connections: 20");
+    log::info!("  • Idle timeout: {}s", idle_timeout_secs);
+    log::info!("  • Max lifetime: {}s", max_lifetime_secs);
     log::info!("  • Using pooler: {}", database_url.contains("pooler") || database_url.contains("pooler.supabase.com"));
     
     Ok(pool)
@@ -252,27 +367,136 @@ pub async fn create_pool(app_handle: Option<&AppHandle>) -> Result<PgPool, Strin
 
 // === Tauri Commands - Using State ===
 
+/// List a user's conversations newest first, optionally only those of one `type`
 #[tauri::command]
 pub async fn db_get_conversations(
     state: State<'_, DbState>,
     user_id: String,
+    type_filter: Option<String>,
 ) -> Result<Vec<Conversation>, String> {
     let conversations = sqlx::query_as::<_, Conversation>(
         r#"
         SELECT id, user_id, title, type, created_at, updated_at
         FROM conversations
         WHERE user_id = $1
+          AND ($2::text IS NULL OR type = $2)
         ORDER BY created_at DESC
         "#,
     )
     .bind(&user_id)
-    .fetch_all(&state.pool)
+    .bind(&type_filter)
+    .fetch_all(&state.pool())
     .await
     .map_err(|e| format!("Failed to fetch conversations: {}", e))?;
 
     Ok(conversations)
 }
 
+/// A conversation type and how many of the user's conversations have it
+#[derive(Debug, Serialize)]
+pub struct ConversationTypeCount {
+    #[serde(rename = "type")]
+    pub r#type: String,
+    pub count: i64,
+}
+
+/// Distinct conversation types for a user with their counts, most common first,
+/// e.g. to build type tabs without loading every conversation
+#[tauri::command]
+pub async fn db_get_conversation_types(
+    state: State<'_, DbState>,
+    user_id: String,
+) -> Result<Vec<ConversationTypeCount>, String> {
+    let rows = sqlx::query(
+        r#"
+        SELECT type, COUNT(*) AS count
+        FROM conversations
+        WHERE user_id = $1
+        GROUP BY type
+        ORDER BY count DESC, type
+        "#,
+    )
+    .bind(&user_id)
+    .fetch_all(&state.pool())
+    .await
+    .map_err(|e| format!("Failed to fetch conversation types: {}", e))?;
+
+    rows.iter()
+        .map(|row| {
+            Ok(ConversationTypeCount {
+                r#type: row.try_get("type")?,
+                count: row.try_get("count")?,
+            })
+        })
+        .collect::<Result<Vec<_>, sqlx::Error>>()
+        .map_err(|e| format!("Failed to read conversation types: {}", e))
+}
+
+/// Conversations that fall in the same relative date range ("Today", "Yesterday", ...)
+#[derive(Debug, Serialize)]
+pub struct ConversationGroup {
+    pub group_label: String,
+    pub conversations: Vec<Conversation>,
+}
+
+/// List a user's conversations newest first, bucketed into Today / Yesterday / This Week /
+/// This Month / Older. `timezone` is an IANA name (e.g. "America/Sao_Paulo") so the day
+/// boundaries match the user's local calendar rather than UTC.
+#[tauri::command]
+pub async fn db_get_conversations_grouped(
+    state: State<'_, DbState>,
+    user_id: String,
+    timezone: String,
+) -> Result<Vec<ConversationGroup>, String> {
+    // Timestamps are stored as UTC without a zone, so convert both sides to local time
+    let rows = sqlx::query(
+        r#"
+        WITH local AS (
+            SELECT id, user_id, title, type, created_at, updated_at,
+                   (created_at AT TIME ZONE 'UTC') AT TIME ZONE $2 AS local_created,
+                   now() AT TIME ZONE $2 AS local_now
+            FROM conversations
+            WHERE user_id = $1
+        )
+        SELECT id, user_id, title, type, created_at, updated_at,
+               CASE
+                   WHEN local_created >= date_trunc('day', local_now) THEN 'Today'
+                   WHEN local_created >= date_trunc('day', local_now) - INTERVAL '1 day' THEN 'Yesterday'
+                   WHEN local_created >= date_trunc('week', local_now) THEN 'This Week'
+                   WHEN local_created >= date_trunc('month', local_now) THEN 'This Month'
+                   ELSE 'Older'
+               END AS group_label
+        FROM local
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(&user_id)
+    .bind(&timezone)
+    .fetch_all(&state.pool())
+    .await
+    .map_err(|e| format!("Failed to fetch conversations: {}", e))?;
+
+    // Rows are sorted newest first, so each label forms one contiguous run
+    let mut groups: Vec<ConversationGroup> = Vec::new();
+    for row in &rows {
+        let group_label: String = row
+            .try_get("group_label")
+            .map_err(|e| format!("Failed to read conversation group: {}", e))?;
+        let conversation = Conversation::from_row(row)
+            .map_err(|e| format!("Failed to read conversation: {}", e))?;
+
+        match groups.last_mut() {
+            Some(group) if group.group_label == group_label => group.conversations.push(conversation),
+            _ => groups.push(ConversationGroup {
+                group_label,
+                conversations: vec![conversation],
+            }),
+        }
+    }
+
+    Ok(groups)
+}
+
 #[tauri::command]
 pub async fn db_get_conversation_by_id(
     state: State<'_, DbState>,
@@ -286,7 +510,7 @@ pub async fn db_get_conversation_by_id(
         "#,
     )
     .bind(conversation_id)
-    .fetch_optional(&state.pool)
+    .fetch_optional(&state.pool())
     .await
     .map_err(|e| format!("Failed to fetch conversation: {}", e))?;
 
@@ -308,7 +532,7 @@ pub async fn db_create_conversation(
     .bind(&input.user_id)
     .bind(&input.title)
     .bind(&input.r#type)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.pool())
     .await
     .map_err(|e| format!("Failed to create conversation: {}", e))?;
 
@@ -331,7 +555,7 @@ pub async fn db_update_conversation(
     )
     .bind(&title)
     .bind(conversation_id)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.pool())
     .await
     .map_err(|e| format!("Failed to update conversation: {}", e))?;
 
@@ -350,13 +574,60 @@ pub async fn db_delete_conversation(
         "#,
     )
     .bind(conversation_id)
-    .execute(&state.pool)
+    .execute(&state.pool())
     .await
     .map_err(|e| format!("Failed to delete conversation: {}", e))?;
 
     Ok(result.rows_affected() > 0)
 }
 
+/// Longest retention window accepted by `db_purge_old_conversations` (about 100 years)
+const MAX_RETENTION_DAYS: i64 = 36_500;
+
+/// Delete a user's conversations not updated in the last `older_than_days` days, along with
+/// their chats, transcriptions and summaries (via ON DELETE CASCADE). Returns how many
+/// conversations were removed.
+#[tauri::command]
+pub async fn db_purge_old_conversations(
+    state: State<'_, DbState>,
+    user_id: String,
+    older_than_days: i64,
+) -> Result<u64, String> {
+    // A zero or negative window would match every conversation
+    if !(1..=MAX_RETENTION_DAYS).contains(&older_than_days) {
+        return Err(format!(
+            "older_than_days must be between 1 and {} (got {})",
+            MAX_RETENTION_DAYS, older_than_days
+        ));
+    }
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(older_than_days);
+
+    let mut tx = state.pool().begin().await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    let result = sqlx::query(
+        r#"
+        DELETE FROM conversations
+        WHERE user_id = $1 AND updated_at < $2
+        "#,
+    )
+    .bind(&user_id)
+    .bind(cutoff.naive_utc())
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to purge conversations: {}", e))?;
+
+    tx.commit().await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    log::info!(
+        "Purged {} conversations older than {} days",
+        result.rows_affected(),
+        older_than_days
+    );
+    Ok(result.rows_affected())
+}
+
 #[tauri::command]
 pub async fn db_get_conversation_messages(
     state: State<'_, DbState>,
@@ -371,7 +642,7 @@ pub async fn db_get_conversation_messages(
         "#,
     )
     .bind(conversation_id)
-    .fetch_all(&state.pool)
+    .fetch_all(&state.pool())
     .await
     .map_err(|e| format!("Failed to fetch conversation messages: {}", e))?;
 
@@ -383,6 +654,8 @@ pub async fn db_create_conversation_message(
     state: State<'_, DbState>,
     input: CreateConversationMessageInput,
 ) -> Result<ConversationMessage, String> {
+    let role = MessageRole::parse(&input.role)?;
+
     let message = sqlx::query_as::<_, ConversationMessage>(
         r#"
         INSERT INTO conversation_messages (conversation_id, user_id, role, content)
@@ -392,9 +665,9 @@ pub async fn db_create_conversation_message(
     )
     .bind(input.conversation_id)
     .bind(&input.user_id)
-    .bind(&input.role)
+    .bind(role.as_str())
     .bind(&input.content)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.pool())
     .await
     .map_err(|e| format!("Failed to create conversation message: {}", e))?;
 
@@ -415,7 +688,7 @@ pub async fn db_get_chats(
         "#,
     )
     .bind(&user_id)
-    .fetch_all(&state.pool)
+    .fetch_all(&state.pool())
     .await
     .map_err(|e| format!("Failed to fetch chats: {}", e))?;
 
@@ -435,7 +708,7 @@ pub async fn db_get_chat_by_id(
         "#,
     )
     .bind(chat_id)
-    .fetch_optional(&state.pool)
+    .fetch_optional(&state.pool())
     .await
     .map_err(|e| format!("Failed to fetch chat: {}", e))?;
 
@@ -457,7 +730,7 @@ pub async fn db_create_chat(
     .bind(&input.conversation_id)
     .bind(&input.user_id)
     .bind(&input.title)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.pool())
     .await
     .map_err(|e| format!("Failed to create chat: {}", e))?;
 
@@ -480,7 +753,7 @@ pub async fn db_update_chat(
     )
     .bind(&title)
     .bind(chat_id)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.pool())
     .await
     .map_err(|e| format!("Failed to update chat: {}", e))?;
 
@@ -499,13 +772,75 @@ pub async fn db_delete_chat(
         "#,
     )
     .bind(chat_id)
-    .execute(&state.pool)
+    .execute(&state.pool())
     .await
     .map_err(|e| format!("Failed to delete chat: {}", e))?;
 
     Ok(result.rows_affected() > 0)
 }
 
+/// Build a `Message` from a messages row, loading its attachments as data URLs
+async fn message_with_attachments(
+    pool: &PgPool,
+    row: &sqlx::postgres::PgRow,
+) -> Result<Message, String> {
+    let message_id: Uuid = row.try_get("id")
+        .map_err(|e| format!("Failed to get message id: {}", e))?;
+    
+    // Fetch attachments for this message
+    // Use explicit type casting to ensure UUID type is correctly inferred
+    let attachments = match sqlx::query(
+        r#"
+        SELECT attachment_data, mime_type
+        FROM message_attachments
+        WHERE message_id::text = $1::text
+        ORDER BY created_at ASC
+        "#,
+    )
+    .bind(message_id.to_string())
+    .fetch_all(pool)
+    .await
+    {
+        Ok(rows) => {
+            rows.into_iter()
+                .map(|att_row| {
+                    let base64_data: String = att_row.try_get("attachment_data")
+                        .unwrap_or_default();
+                    let mime_type: String = att_row.try_get("mime_type")
+                        .unwrap_or_else(|_| "image/png".to_string());
+                    
+                    // Convert to data URL
+                    format!("data:{};base64,{}", mime_type, base64_data)
+                })
+                .collect::<Vec<String>>()
+        }
+        Err(e) => {
+            // Log error but don't fail the entire request
+            eprintln!("Failed to fetch attachments for message {}: {}", message_id, e);
+            Vec::new()
+        }
+    };
+
+    Ok(Message {
+        id: message_id,
+        chat_id: row.try_get("chat_id")
+            .map_err(|e| format!("Failed to get chat_id: {}", e))?,
+        role: row.try_get("role")
+            .map_err(|e| format!("Failed to get role: {}", e))?,
+        content: row.try_get("content")
+            .map_err(|e| format!("Failed to get content: {}", e))?,
+        created_at: row
+            .try_get::<chrono::NaiveDateTime, _>("created_at")
+            .map_err(|e| format!("Failed to get created_at: {}", e))?
+            .and_utc(),
+        attachments: if attachments.is_empty() {
+            None
+        } else {
+            Some(attachments)
+        },
+    })
+}
+
 #[tauri::command]
 pub async fn db_get_messages(
     state: State<'_, DbState>,
@@ -521,79 +856,101 @@ pub async fn db_get_messages(
         "#,
     )
     .bind(chat_id)
-    .fetch_all(&state.pool)
+    .fetch_all(&state.pool())
     .await
     .map_err(|e| format!("Failed to fetch messages: {}", e))?;
 
     // Then, fetch attachments for each message and convert to data URLs
+    let pool = state.pool();
     let mut messages = Vec::new();
     for row in messages_rows {
-        let message_id: Uuid = row.try_get("id")
-            .map_err(|e| format!("Failed to get message id: {}", e))?;
-        
-        // Fetch attachments for this message
-        // Use explicit type casting to ensure UUID type is correctly inferred
-        let attachments = match sqlx::query(
-            r#"
-            SELECT attachment_data, mime_type
-            FROM message_attachments
-            WHERE message_id::text = $1::text
-            ORDER BY created_at ASC
-            "#,
-        )
-        .bind(message_id.to_string())
-        .fetch_all(&state.pool)
-        .await
-        {
-            Ok(rows) => {
-                rows.into_iter()
-                    .map(|att_row| {
-                        let base64_data: String = att_row.try_get("attachment_data")
-                            .unwrap_or_default();
-                        let mime_type: String = att_row.try_get("mime_type")
-                            .unwrap_or_else(|_| "image/png".to_string());
-                        
-                        // Convert to data URL
-                        format!("data:{};base64,{}", mime_type, base64_data)
-                    })
-                    .collect::<Vec<String>>()
-            }
-            Err(e) => {
-                // Log error but don't fail the entire request
-                eprintln!("Failed to fetch attachments for message {}: {}", message_id, e);
-                Vec::new()
-            }
-        };
-
-        messages.push(Message {
-            id: row.try_get("id")
-                .map_err(|e| format!("Failed to get id: {}", e))?,
-            chat_id: row.try_get("chat_id")
-                .map_err(|e| format!("Failed to get chat_id: {}", e))?,
-            role: row.try_get("role")
-                .map_err(|e| format!("Failed to get role: {}", e))?,
-            content: row.try_get("content")
-                .map_err(|e| format!("Failed to get content: {}", e))?,
-            created_at: row
-                .try_get::<chrono::NaiveDateTime, _>("created_at")
-                .map_err(|e| format!("Failed to get created_at: {}", e))?
-                .and_utc(),
-            attachments: if attachments.is_empty() {
-                None
-            } else {
-                Some(attachments)
-            },
-        });
+        messages.push(message_with_attachments(&pool, &row).await?);
     }
 
     Ok(messages)
 }
 
+/// Largest page `db_get_messages_paged` will return
+const MAX_MESSAGE_PAGE_SIZE: i64 = 200;
+
+/// Position in a chat's history. Messages inserted together (a user/assistant pair) can
+/// share `created_at`, so the id breaks ties and no message falls between two pages.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageCursor {
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub id: Uuid,
+}
+
+/// One page of a chat's history, oldest message first
+#[derive(Debug, Serialize)]
+pub struct MessagePage {
+    pub messages: Vec<Message>,
+    /// Pass as `before` to load the next (older) page; None when there are no older messages
+    pub next_cursor: Option<MessageCursor>,
+}
+
+/// Fetch up to `limit` messages older than `before` (or the latest ones when no cursor is
+/// given), for infinite scroll that loads the newest messages first
+#[tauri::command]
+pub async fn db_get_messages_paged(
+    state: State<'_, DbState>,
+    chat_id: Uuid,
+    before: Option<MessageCursor>,
+    limit: i64,
+) -> Result<MessagePage, String> {
+    let limit = limit.clamp(1, MAX_MESSAGE_PAGE_SIZE);
+
+    // Fetch one extra row to know whether an older page exists
+    let mut rows = sqlx::query(
+        r#"
+        SELECT m.id, m.chat_id, m.role, m.content, m.created_at
+        FROM messages m
+        WHERE m.chat_id = $1
+          AND ($2::timestamp IS NULL OR (m.created_at, m.id) < ($2, $3::uuid))
+        ORDER BY m.created_at DESC, m.id DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(chat_id)
+    .bind(before.as_ref().map(|b| b.created_at.naive_utc()))
+    .bind(before.as_ref().map(|b| b.id))
+    .bind(limit + 1)
+    .fetch_all(&state.pool())
+    .await
+    .map_err(|e| format!("Failed to fetch messages: {}", e))?;
+
+    let has_more = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+    rows.reverse();
+
+    let pool = state.pool();
+    let mut messages = Vec::with_capacity(rows.len());
+    for row in &rows {
+        messages.push(message_with_attachments(&pool, row).await?);
+    }
+
+    let next_cursor = if has_more {
+        messages.first().map(|m| MessageCursor {
+            created_at: m.created_at,
+            id: m.id,
+        })
+    } else {
+        None
+    };
+
+    Ok(MessagePage {
+        messages,
+        next_cursor,
+    })
+}
+
 #[tauri::command]
 pub async fn db_create_message(
     state: State<'_, DbState>,
     input: CreateMessageInput,
 ) -> Result<Message, String> {
+    let role = MessageRole::parse(&input.role)?;
+
     let message = sqlx::query_as::<_, Message>(
         r#"
         INSERT INTO messages (chat_id, role, content)
@@ -602,9 +959,9 @@ pub async fn db_create_message(
         "#,
     )
     .bind(input.chat_id)
-    .bind(&input.role)
+    .bind(role.as_str())
     .bind(&input.content)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.pool())
     .await
     .map_err(|e| format!("Failed to create message: {}", e))?;
 
@@ -623,7 +980,7 @@ pub async fn db_delete_message(
         "#,
     )
     .bind(message_id)
-    .execute(&state.pool)
+    .execute(&state.pool())
     .await
     .map_err(|e| format!("Failed to delete message: {}", e))?;
 
@@ -633,13 +990,302 @@ pub async fn db_delete_message(
 #[tauri::command]
 pub async fn db_test_connection(state: State<'_, DbState>) -> Result<bool, String> {
     sqlx::query("SELECT 1")
-        .execute(&state.pool)
+        .execute(&state.pool())
         .await
         .map_err(|e| format!("Database connection test failed: {}", e))?;
 
     Ok(true)
 }
 
+/// Save a connection string in the settings store and reconnect the pool to it
+/// The URL is validated and a connection is made before anything is saved, so a bad
+/// URL never replaces a working one.
+#[tauri::command]
+pub async fn set_database_url(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    url: String,
+) -> Result<(), String> {
+    let url = url.trim().to_string();
+    validate_database_url(&url)?;
+
+    let pool = connect_pool(&url).await?;
+    settings::set_value(&app, DATABASE_URL_SETTING, serde_json::Value::String(url))?;
+
+    let old_pool = state.replace_pool(pool);
+    old_pool.close().await;
+    log::info!("✓ Reconnected database pool with the saved URL");
+
+    Ok(())
+}
+
+/// Rebuild the pool from the current configuration (e.g. after sleep or a network drop)
+/// and swap it in, emitting `db_reconnected` on success.
+#[tauri::command]
+pub async fn db_reconnect(app: AppHandle, state: State<'_, DbState>) -> Result<(), String> {
+    log::info!("🔄 Reconnecting database pool...");
+    let pool = create_pool(Some(&app)).await?;
+
+    let old_pool = state.replace_pool(pool);
+    old_pool.close().await;
+    log::info!("✓ Database pool reconnected");
+
+    let _ = app.emit("db_reconnected", ());
+    Ok(())
+}
+
+#[derive(Serialize, Clone)]
+struct DbConnectivityPayload {
+    online: bool,
+}
+
+/// Background task that pings the pool and emits `db_connectivity` when it goes
+/// offline or comes back, so the UI can show a banner before commands start failing
+#[derive(Default)]
+pub struct ConnectivityMonitor {
+    shutdown: Mutex<Option<oneshot::Sender<()>>>,
+}
+
+impl ConnectivityMonitor {
+    /// Start polling every `DB_HEALTH_CHECK_INTERVAL_SECS` seconds (default 15)
+    /// The pool is looked up on each tick, so it keeps working after `db_reconnect`.
+    pub fn start(&self, app: AppHandle) {
+        let interval_secs = env_duration_secs("DB_HEALTH_CHECK_INTERVAL_SECS", 15);
+        let period = std::time::Duration::from_secs(interval_secs);
+        let (shutdown, mut shutdown_rx) = oneshot::channel::<()>();
+
+        if let Some(previous) = self.shutdown.lock().unwrap().replace(shutdown) {
+            let _ = previous.send(());
+        }
+
+        tauri::async_runtime::spawn(async move {
+            // setup only gets this far with a connected pool
+            let mut online = true;
+            let mut ticker = tokio::time::interval(period);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticker.tick().await;
+
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    _ = ticker.tick() => {}
+                }
+
+                let pool = app.state::<DbState>().pool();
+                let now_online = matches!(
+                    tokio::time::timeout(period, sqlx::query("SELECT 1").execute(&pool)).await,
+                    Ok(Ok(_))
+                );
+
+                if now_online != online {
+                    online = now_online;
+                    if online {
+                        log::info!("✓ Database connectivity restored");
+                    } else {
+                        log::warn!("⚠ Database connectivity lost");
+                    }
+                    let _ = app.emit("db_connectivity", DbConnectivityPayload { online });
+                }
+            }
+
+            log::info!("Database connectivity monitor stopped");
+        });
+
+        log::info!("✓ Database connectivity monitor running every {}s", interval_secs);
+    }
+
+    pub fn stop(&self) {
+        if let Some(shutdown) = self.shutdown.lock().unwrap().take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+/// Tables that `db_count` can count rows for (all scoped to a user)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum CountTarget {
+    Conversations,
+    ConversationMessages,
+    Chats,
+    Messages,
+    Transcriptions,
+}
+
+impl CountTarget {
+    fn query(self) -> &'static str {
+        match self {
+            CountTarget::Conversations => {
+                "SELECT COUNT(*) FROM conversations WHERE user_id = $1"
+            }
+            CountTarget::ConversationMessages => {
+                "SELECT COUNT(*) FROM conversation_messages WHERE user_id = $1"
+            }
+            CountTarget::Chats => "SELECT COUNT(*) FROM chats WHERE user_id = $1",
+            // messages have no user_id of their own, so scope them through their chat
+            CountTarget::Messages => {
+                "SELECT COUNT(*) FROM messages m INNER JOIN chats c ON m.chat_id = c.id WHERE c.user_id = $1"
+            }
+            CountTarget::Transcriptions => {
+                "SELECT COUNT(*) FROM transcriptions WHERE user_id = $1"
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn db_count(
+    state: State<'_, DbState>,
+    table: CountTarget,
+    user_id: String,
+) -> Result<i64, String> {
+    let count: i64 = sqlx::query_scalar(table.query())
+        .bind(&user_id)
+        .fetch_one(&state.pool())
+        .await
+        .map_err(|e| format!("Failed to count {:?}: {}", table, e))?;
+
+    Ok(count)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: usize,
+    pub in_use: u32,
+    pub max: u32,
+    pub latency_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// Report connection pool usage plus a timed `SELECT 1` latency sample
+#[tauri::command]
+pub async fn db_pool_stats(state: State<'_, DbState>) -> Result<PoolStats, String> {
+    let pool = &state.pool();
+
+    // Time a trivial round-trip (this also acquires a connection, like a real query would)
+    let start = std::time::Instant::now();
+    let (latency_ms, error) = match sqlx::query("SELECT 1").execute(pool).await {
+        Ok(_) => (Some(start.elapsed().as_secs_f64() * 1000.0), None),
+        Err(e) => (None, Some(format!("Latency probe failed: {}", e))),
+    };
+
+    let size = pool.size();
+    let idle = pool.num_idle();
+    let in_use = size.saturating_sub(idle as u32);
+    let max = pool.options().get_max_connections();
+
+    if in_use >= max {
+        log::warn!(
+            "⚠ Database pool saturated: {}/{} connections in use",
+            in_use,
+            max
+        );
+    }
+
+    Ok(PoolStats {
+        size,
+        idle,
+        in_use,
+        max,
+        latency_ms,
+        error,
+    })
+}
+
+/// Tables and columns the commands in this module rely on
+const REQUIRED_SCHEMA: &[(&str, &[&str])] = &[
+    ("conversations", &["id", "user_id", "title", "type", "created_at", "updated_at"]),
+    ("conversation_messages", &["id", "conversation_id", "user_id", "role", "content", "created_at"]),
+    ("chats", &["id", "conversation_id", "user_id", "title", "created_at", "updated_at"]),
+    ("messages", &["id", "chat_id", "role", "content", "created_at"]),
+    ("message_attachments", &["message_id", "attachment_data", "mime_type", "created_at"]),
+    ("summaries", &["id", "conversation_id", "user_id", "title", "content", "created_at", "updated_at"]),
+    ("transcriptions", &["id", "conversation_id", "user_id", "title", "created_at", "updated_at"]),
+    ("transcription_segments", &["id", "transcription_id", "text", "start_time", "end_time", "created_at"]),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableSchemaReport {
+    pub table: String,
+    pub present: bool,
+    pub missing_columns: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchemaReport {
+    pub ok: bool,
+    pub tables: Vec<TableSchemaReport>,
+}
+
+/// Compare the live database against the tables/columns this app expects (read-only)
+#[tauri::command]
+pub async fn db_check_schema(state: State<'_, DbState>) -> Result<SchemaReport, String> {
+    let table_names: Vec<String> = REQUIRED_SCHEMA
+        .iter()
+        .map(|(table, _)| table.to_string())
+        .collect();
+
+    let rows = sqlx::query(
+        r#"
+        SELECT table_name::text AS table_name, column_name::text AS column_name
+        FROM information_schema.columns
+        WHERE table_schema = current_schema()
+          AND table_name = ANY($1)
+        "#,
+    )
+    .bind(&table_names)
+    .fetch_all(&state.pool())
+    .await
+    .map_err(|e| format!("Failed to read database schema: {}", e))?;
+
+    let mut existing: std::collections::HashMap<String, Vec<String>> =
+        std::collections::HashMap::new();
+    for row in rows {
+        let table: String = row
+            .try_get("table_name")
+            .map_err(|e| format!("Failed to get table_name: {}", e))?;
+        let column: String = row
+            .try_get("column_name")
+            .map_err(|e| format!("Failed to get column_name: {}", e))?;
+        existing.entry(table).or_default().push(column);
+    }
+
+    let tables: Vec<TableSchemaReport> = REQUIRED_SCHEMA
+        .iter()
+        .map(|(table, columns)| match existing.get(*table) {
+            Some(present_columns) => TableSchemaReport {
+                table: table.to_string(),
+                present: true,
+                missing_columns: columns
+                    .iter()
+                    .filter(|c| !present_columns.iter().any(|p| p == *c))
+                    .map(|c| c.to_string())
+                    .collect(),
+            },
+            None => TableSchemaReport {
+                table: table.to_string(),
+                present: false,
+                missing_columns: columns.iter().map(|c| c.to_string()).collect(),
+            },
+        })
+        .collect();
+
+    let ok = tables
+        .iter()
+        .all(|t| t.present && t.missing_columns.is_empty());
+
+    if !ok {
+        log::warn!("⚠ Database schema is out of date: {:?}", tables
+            .iter()
+            .filter(|t| !t.present || !t.missing_columns.is_empty())
+            .map(|t| &t.table)
+            .collect::<Vec<_>>());
+    }
+
+    Ok(SchemaReport { ok, tables })
+}
+
 // === Additional Types ===
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -698,6 +1344,23 @@ impl FromRow<'_, sqlx::postgres::PgRow> for Transcription {
     }
 }
 
+/// A transcription row plus how many segments it holds, for list views
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TranscriptionWithCount {
+    #[serde(flatten)]
+    pub transcription: Transcription,
+    pub segment_count: i64,
+}
+
+impl FromRow<'_, sqlx::postgres::PgRow> for TranscriptionWithCount {
+    fn from_row(row: &sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        Ok(TranscriptionWithCount {
+            transcription: Transcription::from_row(row)?,
+            segment_count: row.try_get("segment_count")?,
+        })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TranscriptionSegment {
     pub id: Uuid,
@@ -772,7 +1435,7 @@ pub async fn db_get_summary_by_conversation_id(
         "#,
     )
     .bind(conversation_id)
-    .fetch_optional(&state.pool)
+    .fetch_optional(&state.pool())
     .await
     .map_err(|e| format!("Failed to fetch summary: {}", e))?;
 
@@ -795,7 +1458,7 @@ pub async fn db_create_summary(
     .bind(&input.user_id)
     .bind(&input.title)
     .bind(&input.content)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.pool())
     .await
     .map_err(|e| format!("Failed to create summary: {}", e))?;
 
@@ -821,7 +1484,7 @@ pub async fn db_update_summary(
     .bind(&input.title)
     .bind(&input.content)
     .bind(input.summary_id)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.pool())
     .await
     .map_err(|e| format!("Failed to update summary: {}", e))?;
 
@@ -834,17 +1497,18 @@ pub async fn db_update_summary(
 pub async fn db_get_transcriptions(
     state: State<'_, DbState>,
     user_id: String,
-) -> Result<Vec<Transcription>, String> {
-    let transcriptions = sqlx::query_as::<_, Transcription>(
+) -> Result<Vec<TranscriptionWithCount>, String> {
+    let transcriptions = sqlx::query_as::<_, TranscriptionWithCount>(
         r#"
-        SELECT id, conversation_id, user_id, title, created_at, updated_at
-        FROM transcriptions
-        WHERE user_id = $1
-        ORDER BY created_at DESC
+        SELECT t.id, t.conversation_id, t.user_id, t.title, t.created_at, t.updated_at,
+            (SELECT COUNT(*) FROM transcription_segments ts WHERE ts.transcription_id = t.id) AS segment_count
+        FROM transcriptions t
+        WHERE t.user_id = $1
+        ORDER BY t.created_at DESC
         "#,
     )
     .bind(&user_id)
-    .fetch_all(&state.pool)
+    .fetch_all(&state.pool())
     .await
     .map_err(|e| format!("Failed to fetch transcriptions: {}", e))?;
 
@@ -864,7 +1528,7 @@ pub async fn db_get_transcription_by_id(
         "#,
     )
     .bind(transcription_id)
-    .fetch_optional(&state.pool)
+    .fetch_optional(&state.pool())
     .await
     .map_err(|e| format!("Failed to fetch transcription: {}", e))?;
 
@@ -886,13 +1550,131 @@ pub async fn db_create_transcription(
     .bind(&input.conversation_id)
     .bind(&input.user_id)
     .bind(&input.title)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.pool())
     .await
     .map_err(|e| format!("Failed to create transcription: {}", e))?;
 
     Ok(transcription)
 }
 
+/// Rename a transcription and/or link it to a conversation; omitted fields are left unchanged
+#[tauri::command]
+pub async fn db_update_transcription(
+    state: State<'_, DbState>,
+    transcription_id: Uuid,
+    title: Option<String>,
+    conversation_id: Option<Uuid>,
+) -> Result<Transcription, String> {
+    let transcription = sqlx::query_as::<_, Transcription>(
+        r#"
+        UPDATE transcriptions
+        SET title = COALESCE($1, title),
+            conversation_id = COALESCE($2, conversation_id),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $3
+        RETURNING id, conversation_id, user_id, title, created_at, updated_at
+        "#,
+    )
+    .bind(&title)
+    .bind(conversation_id)
+    .bind(transcription_id)
+    .fetch_one(&state.pool())
+    .await
+    .map_err(|e| format!("Failed to update transcription: {}", e))?;
+
+    Ok(transcription)
+}
+
+/// Create a transcription and batch-insert its `(text, start, end)` segments in one
+/// transaction, so a failure never leaves a transcription with only part of its segments
+pub(crate) async fn create_transcription_with_segments(
+    pool: &PgPool,
+    input: &CreateTranscriptionInput,
+    segments: &[(&str, f64, f64)],
+) -> Result<Uuid, String> {
+    let mut tx = pool.begin().await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    let transcription_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO transcriptions (conversation_id, user_id, title)
+        VALUES ($1, $2, $3)
+        RETURNING id
+        "#,
+    )
+    .bind(&input.conversation_id)
+    .bind(&input.user_id)
+    .bind(&input.title)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to create transcription: {}", e))?;
+
+    let texts: Vec<String> = segments.iter().map(|s| s.0.to_string()).collect();
+    let starts: Vec<f32> = segments.iter().map(|s| s.1 as f32).collect();
+    let ends: Vec<f32> = segments.iter().map(|s| s.2 as f32).collect();
+
+    sqlx::query(
+        r#"
+        INSERT INTO transcription_segments (transcription_id, text, start_time, end_time)
+        SELECT $1, text, start_time, end_time
+        FROM UNNEST($2::text[], $3::real[], $4::real[]) AS s(text, start_time, end_time)
+        "#,
+    )
+    .bind(transcription_id)
+    .bind(&texts)
+    .bind(&starts)
+    .bind(&ends)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to create transcription segments: {}", e))?;
+
+    tx.commit().await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(transcription_id)
+}
+
+/// Delete a transcription and all of its segments; returns how many segments were removed
+#[tauri::command]
+pub async fn db_delete_transcription(
+    state: State<'_, DbState>,
+    transcription_id: Uuid,
+) -> Result<u64, String> {
+    let mut tx = state.pool().begin().await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    let segments = sqlx::query(
+        r#"
+        DELETE FROM transcription_segments
+        WHERE transcription_id = $1
+        "#,
+    )
+    .bind(transcription_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to delete transcription segments: {}", e))?;
+
+    let transcription = sqlx::query(
+        r#"
+        DELETE FROM transcriptions
+        WHERE id = $1
+        "#,
+    )
+    .bind(transcription_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to delete transcription: {}", e))?;
+
+    if transcription.rows_affected() == 0 {
+        return Err(format!("Transcription {} not found", transcription_id));
+    }
+
+    tx.commit().await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(segments.rows_affected())
+}
+
 #[tauri::command]
 pub async fn db_get_transcription_segments(
     state: State<'_, DbState>,
@@ -907,7 +1689,7 @@ pub async fn db_get_transcription_segments(
         "#,
     )
     .bind(transcription_id)
-    .fetch_all(&state.pool)
+    .fetch_all(&state.pool())
     .await
     .map_err(|e| format!("Failed to fetch transcription segments: {}", e))?;
 
@@ -920,7 +1702,7 @@ pub async fn db_create_transcription_segment(
     input: CreateTranscriptionSegmentInput,
 ) -> Result<TranscriptionSegment, String> {
     // Start a transaction for atomicity
-    let mut tx = state.pool.begin().await
+    let mut tx = state.pool().begin().await
         .map_err(|e| format!("Failed to start transaction: {}", e))?;
 
     let segment = sqlx::query_as::<_, TranscriptionSegment>(
@@ -970,13 +1752,229 @@ pub async fn db_get_transcription_segments_by_conversation_id(
         "#,
     )
     .bind(conversation_id)
-    .fetch_all(&state.pool)
+    .fetch_all(&state.pool())
     .await
     .map_err(|e| format!("Failed to fetch transcription segments: {}", e))?;
 
     Ok(segments)
 }
 
+/// `[mm:ss]` for a segment start; minutes keep counting past an hour
+fn transcript_timestamp(secs: f64) -> String {
+    let total = secs.max(0.0) as u64;
+    format!("[{:02}:{:02}]", total / 60, total % 60)
+}
+
+/// The conversation's whole transcript as one string, one segment per line, for copying
+/// With `include_timestamps`, each line starts with its `[mm:ss]` start time (segments
+/// without one are left unprefixed).
+#[tauri::command]
+pub async fn db_get_transcript_text(
+    state: State<'_, DbState>,
+    conversation_id: Uuid,
+    include_timestamps: bool,
+) -> Result<String, String> {
+    let segments = db_get_transcription_segments_by_conversation_id(state, conversation_id).await?;
+
+    let lines: Vec<String> = segments
+        .iter()
+        .filter(|s| !s.text.trim().is_empty())
+        .map(|s| match s.start_time {
+            Some(start) if include_timestamps => {
+                format!("{} {}", transcript_timestamp(start), s.text.trim())
+            }
+            _ => s.text.trim().to_string(),
+        })
+        .collect();
+
+    Ok(lines.join("\n"))
+}
+
+/// One of the user's transcriptions whose conversation reference no longer resolves
+#[derive(Serialize, Debug)]
+pub struct DanglingTranscription {
+    pub id: Uuid,
+    pub title: Option<String>,
+    pub conversation_id: Uuid,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A segment of a `DanglingTranscription`, unreachable through any conversation
+#[derive(Serialize, Debug)]
+pub struct OrphanedSegment {
+    pub id: Uuid,
+    pub transcription_id: Uuid,
+    pub text: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct OrphanScan {
+    pub transcriptions: Vec<DanglingTranscription>,
+    pub segments: Vec<OrphanedSegment>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanRepairStrategy {
+    /// Delete the dangling transcriptions and their segments
+    Delete,
+    /// Give each dangling transcription its own conversation, so the segments show up again
+    CreatePlaceholders,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct OrphanRepairReport {
+    pub segments_deleted: u64,
+    pub transcriptions_deleted: u64,
+    pub conversations_created: u64,
+}
+
+/// The user's transcriptions that reference a conversation which doesn't exist. Transcriptions
+/// with no conversation at all are left alone, and segments without a transcription row have
+/// no owner to check, so neither is ever touched here.
+const DANGLING_TRANSCRIPTIONS_WHERE: &str = r#"
+    FROM transcriptions t
+    WHERE t.user_id = $1
+      AND t.conversation_id IS NOT NULL
+      AND NOT EXISTS (SELECT 1 FROM conversations c WHERE c.id = t.conversation_id)
+"#;
+
+/// List the user's dangling transcriptions and the segments stranded under them
+#[tauri::command]
+pub async fn db_find_orphans(
+    state: State<'_, DbState>,
+    user_id: String,
+) -> Result<OrphanScan, String> {
+    let transcriptions: Vec<(Uuid, Option<String>, Uuid, chrono::NaiveDateTime)> =
+        sqlx::query_as(&format!(
+            r#"
+            SELECT t.id, t.title, t.conversation_id, t.created_at
+            {}
+            ORDER BY t.created_at ASC
+            "#,
+            DANGLING_TRANSCRIPTIONS_WHERE
+        ))
+        .bind(&user_id)
+        .fetch_all(&state.pool())
+        .await
+        .map_err(|e| format!("Failed to find dangling transcriptions: {}", e))?;
+
+    let segments: Vec<(Uuid, Uuid, String, chrono::NaiveDateTime)> = sqlx::query_as(&format!(
+        r#"
+        SELECT ts.id, ts.transcription_id, ts.text, ts.created_at
+        FROM transcription_segments ts
+        WHERE ts.transcription_id IN (SELECT t.id {})
+        ORDER BY ts.created_at ASC
+        "#,
+        DANGLING_TRANSCRIPTIONS_WHERE
+    ))
+    .bind(&user_id)
+    .fetch_all(&state.pool())
+    .await
+    .map_err(|e| format!("Failed to find orphaned segments: {}", e))?;
+
+    Ok(OrphanScan {
+        transcriptions: transcriptions
+            .into_iter()
+            .map(|(id, title, conversation_id, created_at)| DanglingTranscription {
+                id,
+                title,
+                conversation_id,
+                created_at: created_at.and_utc(),
+            })
+            .collect(),
+        segments: segments
+            .into_iter()
+            .map(|(id, transcription_id, text, created_at)| OrphanedSegment {
+                id,
+                transcription_id,
+                text,
+                created_at: created_at.and_utc(),
+            })
+            .collect(),
+    })
+}
+
+/// Fix what `db_find_orphans` reports, all in one transaction
+#[tauri::command]
+pub async fn db_repair_orphans(
+    state: State<'_, DbState>,
+    user_id: String,
+    strategy: OrphanRepairStrategy,
+) -> Result<OrphanRepairReport, String> {
+    let mut tx = state.pool().begin().await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let mut report = OrphanRepairReport::default();
+
+    match strategy {
+        OrphanRepairStrategy::Delete => {
+            report.segments_deleted = sqlx::query(&format!(
+                "DELETE FROM transcription_segments WHERE transcription_id IN (SELECT t.id {})",
+                DANGLING_TRANSCRIPTIONS_WHERE
+            ))
+            .bind(&user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to delete orphaned segments: {}", e))?
+            .rows_affected();
+
+            report.transcriptions_deleted = sqlx::query(&format!(
+                "DELETE FROM transcriptions WHERE id IN (SELECT t.id {})",
+                DANGLING_TRANSCRIPTIONS_WHERE
+            ))
+            .bind(&user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to delete dangling transcriptions: {}", e))?
+            .rows_affected();
+        }
+        OrphanRepairStrategy::CreatePlaceholders => {
+            // One conversation per transcription, as the conversation_id migration does
+            let dangling: Vec<(Uuid, Option<String>, chrono::NaiveDateTime, chrono::NaiveDateTime)> =
+                sqlx::query_as(&format!(
+                    "SELECT t.id, t.title, t.created_at, t.updated_at {}",
+                    DANGLING_TRANSCRIPTIONS_WHERE
+                ))
+                .bind(&user_id)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to find dangling transcriptions: {}", e))?;
+
+            for (transcription_id, title, created_at, updated_at) in dangling {
+                let conversation_id: Uuid = sqlx::query_scalar(
+                    r#"
+                    INSERT INTO conversations (user_id, title, type, created_at, updated_at)
+                    VALUES ($1, $2, 'transcription', $3, $4)
+                    RETURNING id
+                    "#,
+                )
+                .bind(&user_id)
+                .bind(&title)
+                .bind(created_at)
+                .bind(updated_at)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to create conversation: {}", e))?;
+
+                sqlx::query("UPDATE transcriptions SET conversation_id = $1 WHERE id = $2")
+                    .bind(conversation_id)
+                    .bind(transcription_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Failed to link transcription to conversation: {}", e))?;
+                report.conversations_created += 1;
+            }
+        }
+    }
+
+    tx.commit().await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    log::info!("Repaired orphaned transcription data ({:?}): {:?}", strategy, report);
+    Ok(report)
+}
+
 #[tauri::command]
 pub async fn db_get_chat_by_conversation_id(
     state: State<'_, DbState>,
@@ -991,9 +1989,77 @@ pub async fn db_get_chat_by_conversation_id(
         "#,
     )
     .bind(conversation_id)
-    .fetch_optional(&state.pool)
+    .fetch_optional(&state.pool())
     .await
     .map_err(|e| format!("Failed to fetch chat by conversation ID: {}", e))?;
 
     Ok(chat)
-}
\ No newline at end of file
+}
+
+/// Instruction placed ahead of the transcript in the seeded system message
+const TRANSCRIPT_CHAT_PROMPT: &str = "The user wants to ask questions about the following transcript. Answer using only what it contains, and say so when the transcript doesn't cover something.";
+
+/// Start a chat about a conversation's transcript: creates a chat linked to the conversation
+/// and seeds it with a system message holding the full transcript text. Returns the chat id.
+#[tauri::command]
+pub async fn transcription_to_chat(
+    state: State<'_, DbState>,
+    conversation_id: Uuid,
+) -> Result<Uuid, String> {
+    let conversation = db_get_conversation_by_id(state.clone(), conversation_id)
+        .await?
+        .ok_or_else(|| format!("Conversation {} not found", conversation_id))?;
+    let segments =
+        db_get_transcription_segments_by_conversation_id(state.clone(), conversation_id).await?;
+
+    let transcript = segments
+        .iter()
+        .map(|s| s.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if transcript.is_empty() {
+        return Err("Conversation has no transcript to chat about".to_string());
+    }
+
+    let mut tx = state.pool().begin().await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    let chat_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO chats (conversation_id, user_id, title)
+        VALUES ($1, $2, $3)
+        RETURNING id
+        "#,
+    )
+    .bind(conversation_id)
+    .bind(&conversation.user_id)
+    .bind(&conversation.title)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to create chat: {}", e))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO messages (chat_id, role, content)
+        VALUES ($1, $2, $3)
+        "#,
+    )
+    .bind(chat_id)
+    .bind(MessageRole::System.as_str())
+    .bind(format!("{}\n\nTranscript:\n{}", TRANSCRIPT_CHAT_PROMPT, transcript))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to create message: {}", e))?;
+
+    tx.commit().await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    log::info!(
+        "Created chat {} from the transcript of conversation {} ({} segments)",
+        chat_id,
+        conversation_id,
+        segments.len()
+    );
+    Ok(chat_id)
+}
@@ -0,0 +1,298 @@
+// Semantic search over chat messages: Gemini embeddings stored in a pgvector column.
+// Needs the pgvector extension and db/migrations/create_message_embeddings_table.sql.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+use crate::database::DbState;
+use crate::gemini::{gemini_url, http_client, request_error, resolve_api_key};
+
+const EMBEDDING_MODEL: &str = "text-embedding-004";
+/// Most texts Gemini accepts in one batchEmbedContents call
+const EMBEDDING_BATCH_SIZE: usize = 100;
+const DEFAULT_SEARCH_LIMIT: i64 = 10;
+const MAX_SEARCH_LIMIT: i64 = 50;
+
+#[derive(Serialize)]
+struct EmbedRequest<'a> {
+    model: String,
+    content: EmbedContent<'a>,
+    #[serde(rename = "taskType")]
+    task_type: &'a str,
+}
+
+#[derive(Serialize)]
+struct EmbedContent<'a> {
+    parts: Vec<EmbedPart<'a>>,
+}
+
+#[derive(Serialize)]
+struct EmbedPart<'a> {
+    text: &'a str,
+}
+
+#[derive(Serialize)]
+struct BatchEmbedRequest<'a> {
+    requests: Vec<EmbedRequest<'a>>,
+}
+
+#[derive(Deserialize)]
+struct BatchEmbedResponse {
+    #[serde(default)]
+    embeddings: Vec<Embedding>,
+}
+
+#[derive(Deserialize)]
+struct Embedding {
+    values: Vec<f32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SemanticSearchResult {
+    pub conversation_id: Option<Uuid>,
+    pub chat_id: Uuid,
+    pub message_id: Uuid,
+    pub role: String,
+    pub content: String,
+    /// Cosine similarity to the query (1.0 = identical direction)
+    pub similarity: f64,
+}
+
+/// Fail with a clear message when pgvector or the embeddings table is missing, instead of
+/// surfacing a cryptic "type vector does not exist" from the first query
+async fn ensure_pgvector(pool: &PgPool) -> Result<(), String> {
+    let row = sqlx::query(
+        r#"
+        SELECT
+            EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'vector') AS has_extension,
+            to_regclass('message_embeddings') IS NOT NULL AS has_table
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Failed to check for pgvector: {}", e))?;
+
+    let has_extension: bool = row
+        .try_get("has_extension")
+        .map_err(|e| format!("Failed to get has_extension: {}", e))?;
+    let has_table: bool = row
+        .try_get("has_table")
+        .map_err(|e| format!("Failed to get has_table: {}", e))?;
+
+    if !has_extension {
+        return Err("Semantic search needs the pgvector extension, which is not installed on this database. Enable it (CREATE EXTENSION vector) and run create_message_embeddings_table.sql.".to_string());
+    }
+    if !has_table {
+        return Err("The message_embeddings table is missing. Run the create_message_embeddings_table.sql migration.".to_string());
+    }
+    Ok(())
+}
+
+/// pgvector's text input format, e.g. `[0.1,0.2,0.3]`
+fn vector_literal(values: &[f32]) -> String {
+    let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    format!("[{}]", values.join(","))
+}
+
+/// Embed `texts` with Gemini, one vector per text in the same order
+/// `task_type` is RETRIEVAL_DOCUMENT for stored text and RETRIEVAL_QUERY for searches.
+async fn embed_texts(
+    app: &AppHandle,
+    api_key: &str,
+    texts: &[String],
+    task_type: &str,
+) -> Result<Vec<Vec<f32>>, String> {
+    let url = format!(
+        "{}?key={}",
+        gemini_url(None, &format!("/v1beta/models/{}:batchEmbedContents", EMBEDDING_MODEL))?,
+        api_key
+    );
+    let client = http_client(app)?;
+
+    let mut vectors = Vec::with_capacity(texts.len());
+    for batch in texts.chunks(EMBEDDING_BATCH_SIZE) {
+        let payload = BatchEmbedRequest {
+            requests: batch
+                .iter()
+                .map(|text| EmbedRequest {
+                    model: format!("models/{}", EMBEDDING_MODEL),
+                    content: EmbedContent {
+                        parts: vec![EmbedPart { text }],
+                    },
+                    task_type,
+                })
+                .collect(),
+        };
+
+        let response = client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| request_error(app, e))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("Embedding API Error: {}", error_text));
+        }
+
+        let data: BatchEmbedResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse embedding response: {}", e.without_url()))?;
+        if data.embeddings.len() != batch.len() {
+            return Err(format!(
+                "Embedding API returned {} vectors for {} texts",
+                data.embeddings.len(),
+                batch.len()
+            ));
+        }
+        vectors.extend(data.embeddings.into_iter().map(|e| e.values));
+    }
+
+    Ok(vectors)
+}
+
+/// Embed every chat message in a conversation that doesn't have an embedding yet and store
+/// the vectors. Returns how many messages were embedded.
+#[tauri::command]
+pub async fn db_generate_embeddings(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    conversation_id: Uuid,
+    api_key: Option<String>,
+) -> Result<usize, String> {
+    let api_key = resolve_api_key(&app, api_key)?;
+    let pool = state.pool();
+    ensure_pgvector(&pool).await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT m.id, m.content
+        FROM messages m
+        INNER JOIN chats c ON m.chat_id = c.id
+        LEFT JOIN message_embeddings e ON e.message_id = m.id
+        WHERE c.conversation_id = $1
+          AND e.message_id IS NULL
+          AND btrim(m.content) <> ''
+        ORDER BY m.created_at ASC
+        "#,
+    )
+    .bind(conversation_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to fetch messages to embed: {}", e))?;
+
+    if rows.is_empty() {
+        return Ok(0);
+    }
+
+    let mut ids = Vec::with_capacity(rows.len());
+    let mut texts = Vec::with_capacity(rows.len());
+    for row in &rows {
+        ids.push(
+            row.try_get::<Uuid, _>("id")
+                .map_err(|e| format!("Failed to get id: {}", e))?,
+        );
+        texts.push(
+            row.try_get::<String, _>("content")
+                .map_err(|e| format!("Failed to get content: {}", e))?,
+        );
+    }
+
+    let vectors = embed_texts(&app, &api_key, &texts, "RETRIEVAL_DOCUMENT").await?;
+    let vectors: Vec<String> = vectors.iter().map(|v| vector_literal(v)).collect();
+
+    sqlx::query(
+        r#"
+        INSERT INTO message_embeddings (message_id, embedding)
+        SELECT id, embedding::vector
+        FROM UNNEST($1::uuid[], $2::text[]) AS e(id, embedding)
+        ON CONFLICT (message_id) DO UPDATE SET embedding = EXCLUDED.embedding
+        "#,
+    )
+    .bind(&ids)
+    .bind(&vectors)
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to store embeddings: {}", e))?;
+
+    log::info!(
+        "Embedded {} messages for conversation {}",
+        ids.len(),
+        conversation_id
+    );
+    Ok(ids.len())
+}
+
+/// Find the user's chat messages closest in meaning to `query` (default 10 results, max 50)
+/// Only messages embedded with `db_generate_embeddings` are searched.
+#[tauri::command]
+pub async fn db_semantic_search(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    user_id: String,
+    query: String,
+    api_key: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<SemanticSearchResult>, String> {
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+    let api_key = resolve_api_key(&app, api_key)?;
+    let pool = state.pool();
+    ensure_pgvector(&pool).await?;
+
+    let query_vector = embed_texts(&app, &api_key, &[query], "RETRIEVAL_QUERY")
+        .await?
+        .pop()
+        .ok_or("Embedding API returned no vector for the query")?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT c.conversation_id, m.chat_id, m.id AS message_id, m.role, m.content,
+               1 - (e.embedding <=> $2::vector) AS similarity
+        FROM message_embeddings e
+        INNER JOIN messages m ON e.message_id = m.id
+        INNER JOIN chats c ON m.chat_id = c.id
+        WHERE c.user_id = $1
+        ORDER BY e.embedding <=> $2::vector
+        LIMIT $3
+        "#,
+    )
+    .bind(&user_id)
+    .bind(vector_literal(&query_vector))
+    .bind(limit)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to run semantic search: {}", e))?;
+
+    rows.iter()
+        .map(|row| {
+            Ok(SemanticSearchResult {
+                conversation_id: row
+                    .try_get("conversation_id")
+                    .map_err(|e| format!("Failed to get conversation_id: {}", e))?,
+                chat_id: row
+                    .try_get("chat_id")
+                    .map_err(|e| format!("Failed to get chat_id: {}", e))?,
+                message_id: row
+                    .try_get("message_id")
+                    .map_err(|e| format!("Failed to get message_id: {}", e))?,
+                role: row
+                    .try_get("role")
+                    .map_err(|e| format!("Failed to get role: {}", e))?,
+                content: row
+                    .try_get("content")
+                    .map_err(|e| format!("Failed to get content: {}", e))?,
+                similarity: row
+                    .try_get("similarity")
+                    .map_err(|e| format!("Failed to get similarity: {}", e))?,
+            })
+        })
+        .collect()
+}
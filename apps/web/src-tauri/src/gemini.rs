@@ -1,7 +1,23 @@
 use serde::{Deserialize, Serialize};
 use reqwest::Client;
-use tauri::{AppHandle, Emitter, Runtime};
+use tauri::{AppHandle, Emitter, Runtime, State};
 use futures_util::StreamExt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::database::{self, CreateSummaryInput, DbState, Summary, UpdateSummaryInput};
+use crate::settings;
+use tauri_plugin_keychain::{KeychainExt, KeychainRequest};
+
+/// Keychain entry holding the user's Gemini API key (kept out of settings.json, which is plaintext)
+const GEMINI_API_KEY_ENTRY: &str = "gemini_api_key";
+/// Settings key for an explicit proxy URL (overrides HTTPS_PROXY/HTTP_PROXY)
+const HTTP_PROXY_SETTING: &str = "http_proxy";
+/// Public Gemini API host, used unless a base URL is passed or GEMINI_BASE_URL is set
+const DEFAULT_GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com";
 
 // ----------------------
 // Request Structures
@@ -93,9 +109,75 @@ pub struct SearchEntryPoint {
 // ----------------------
 #[derive(Serialize, Clone)]
 struct StreamPayload {
+    chat_id: String,
     text: Option<String>,
     is_done: bool,
     metadata: Option<GroundingMetadata>,
+    citations: Option<Vec<Citation>>,
+    /// Sanitized search suggestions HTML, which Google requires showing with grounded answers
+    search_entry_point: Option<String>,
+}
+
+/// A normalized web source for footnote-style rendering (1-based index)
+#[derive(Serialize, Clone, Debug)]
+pub struct Citation {
+    pub index: usize,
+    pub title: String,
+    pub uri: String,
+}
+
+/// Build citations from grounding chunks, deduped by URI in first-seen order
+fn build_citations(metadata: &GroundingMetadata) -> Vec<Citation> {
+    let mut citations: Vec<Citation> = Vec::new();
+
+    for chunk in metadata.grounding_chunks.iter().flatten() {
+        if let Some(ref web) = chunk.web {
+            if citations.iter().any(|c| c.uri == web.uri) {
+                continue;
+            }
+            citations.push(Citation {
+                index: citations.len() + 1,
+                title: web.title.clone(),
+                uri: web.uri.clone(),
+            });
+        }
+    }
+
+    citations
+}
+
+/// Elements the search suggestions widget is built from: chips linking to Google and the
+/// SVG icons inside them. Anything else - scripts, frames, `<style>`, SVG animation - is dropped.
+const SEARCH_ENTRY_POINT_TAGS: &[&str] = &["div", "span", "a", "p", "br"];
+const SEARCH_ENTRY_POINT_SVG_TAGS: &[&str] = &["svg", "g", "path", "circle", "rect"];
+/// Attributes kept on any allowed element
+const SEARCH_ENTRY_POINT_ATTRIBUTES: &[&str] = &["class", "id", "aria-label", "aria-hidden", "role"];
+/// Presentation attributes kept on the SVG elements (names are case-sensitive, as html5ever reports them)
+const SEARCH_ENTRY_POINT_SVG_ATTRIBUTES: &[&str] = &[
+    "viewBox", "width", "height", "fill", "fill-rule", "clip-rule", "stroke", "stroke-width",
+    "d", "cx", "cy", "r", "x", "y", "transform",
+];
+/// Elements whose content is dropped along with them instead of being kept as text
+const SEARCH_ENTRY_POINT_DROPPED_CONTENT: &[&str] = &["script", "style", "iframe", "noscript", "template"];
+
+/// Reduce the search suggestions widget to an allow-list of tags and attributes, since the UI
+/// renders it as HTML: links must be absolute http(s) URLs and comments are stripped
+fn sanitize_search_entry_point(html: &str) -> String {
+    let mut builder = ammonia::Builder::empty();
+    builder
+        .add_tags(SEARCH_ENTRY_POINT_TAGS)
+        .add_tags(SEARCH_ENTRY_POINT_SVG_TAGS)
+        .add_clean_content_tags(SEARCH_ENTRY_POINT_DROPPED_CONTENT)
+        .add_generic_attributes(SEARCH_ENTRY_POINT_ATTRIBUTES)
+        .add_tag_attributes("a", &["href", "target"])
+        .url_schemes(["http", "https"].into_iter().collect())
+        .url_relative(ammonia::UrlRelative::Deny)
+        .link_rel(Some("noopener noreferrer"))
+        .strip_comments(true);
+    for tag in SEARCH_ENTRY_POINT_SVG_TAGS {
+        builder.add_tag_attributes(tag, SEARCH_ENTRY_POINT_SVG_ATTRIBUTES);
+    }
+    builder.clean(html).to_string()
 }
 
 // ----------------------
@@ -108,12 +190,134 @@ pub struct ChatMessage {
     pub content: String,
 }
 
+/// Rough token budget for history when none is given (well below gemini-2.5-flash's window)
+const DEFAULT_HISTORY_TOKEN_BUDGET: usize = 200_000;
+
+/// Limits applied to chat history before it's sent; unset fields use the defaults
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct HistoryLimit {
+    /// Keep at most this many of the most recent (non-system) messages
+    pub max_messages: Option<usize>,
+    /// Approximate token budget for the kept history (estimated at ~4 characters per token)
+    pub max_tokens: Option<usize>,
+}
+
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Drop the oldest messages until the history fits the limits
+/// System messages are always kept (and count against the budget); the new prompt is sent
+/// separately so the latest user turn is never truncated.
+fn truncate_history(history: Vec<ChatMessage>, limit: &HistoryLimit) -> Vec<ChatMessage> {
+    let max_messages = limit.max_messages.unwrap_or(usize::MAX);
+    let mut budget = limit.max_tokens.unwrap_or(DEFAULT_HISTORY_TOKEN_BUDGET);
+
+    for msg in history.iter().filter(|m| m.role == "system") {
+        budget = budget.saturating_sub(estimate_tokens(&msg.content));
+    }
+
+    // Walk back from the newest message, keeping whatever fits
+    let mut keep = vec![false; history.len()];
+    let mut kept = 0;
+    for (i, msg) in history.iter().enumerate().rev() {
+        if msg.role == "system" {
+            keep[i] = true;
+            continue;
+        }
+        let tokens = estimate_tokens(&msg.content);
+        if kept >= max_messages || tokens > budget {
+            break;
+        }
+        budget -= tokens;
+        kept += 1;
+        keep[i] = true;
+    }
+    // Older system messages sit before the cut-off point and still need keeping
+    for (i, msg) in history.iter().enumerate() {
+        if msg.role == "system" {
+            keep[i] = true;
+        }
+    }
+
+    let dropped = keep.iter().filter(|k| !**k).count();
+    if dropped > 0 {
+        log::info!("Truncated {} old message(s) from Gemini history", dropped);
+    }
+
+    let mut truncated: Vec<ChatMessage> = history
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(msg, keep)| keep.then_some(msg))
+        .collect();
+
+    // Don't open the conversation with an orphaned assistant reply
+    if dropped > 0 {
+        if let Some(pos) = truncated.iter().position(|m| m.role != "system") {
+            if truncated[pos].role == "assistant" {
+                truncated.remove(pos);
+            }
+        }
+    }
+
+    truncated
+}
+
+/// Appends streamed text to a file so an interrupted generation still leaves a partial result
+struct ResponseFile {
+    writer: BufWriter<File>,
+    last_flush: Instant,
+}
+
+impl ResponseFile {
+    const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+    fn open(path: &str) -> Result<Self, String> {
+        if let Some(parent) = Path::new(path).parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create directory for {}: {}", path, e))?;
+            }
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            last_flush: Instant::now(),
+        })
+    }
+
+    fn append(&mut self, text: &str) {
+        if let Err(e) = self.writer.write_all(text.as_bytes()) {
+            log::warn!("Failed to save Gemini response chunk: {}", e);
+            return;
+        }
+        if self.last_flush.elapsed() >= Self::FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Err(e) = self.writer.flush() {
+            log::warn!("Failed to flush saved Gemini response: {}", e);
+        }
+        self.last_flush = Instant::now();
+    }
+}
+
 // Helper function to process a candidate and emit events
 fn process_candidate<R: Runtime>(
     app: &AppHandle<R>,
     event_name: &str,
+    chat_id: &str,
     gemini_data: &GeminiResponse,
     enable_search: bool,
+    include_search_entry_point: bool,
+    save_file: Option<&mut ResponseFile>,
 ) {
     if let Some(candidates) = &gemini_data.candidates {
         if let Some(candidate) = candidates.first() {
@@ -121,9 +325,27 @@ fn process_candidate<R: Runtime>(
             let text = candidate.content.as_ref()
                 .and_then(|c| c.parts.first())
                 .map(|p| p.text.clone());
+
+            if let (Some(file), Some(text)) = (save_file, text.as_deref()) {
+                file.append(text);
+            }
             
             // Safely extract metadata if it exists
-            let metadata = candidate.grounding_metadata.clone();
+            let mut metadata = candidate.grounding_metadata.clone();
+
+            // The suggestions widget ends up in the page as HTML, so it is only forwarded
+            // sanitized - also inside `metadata` - and dropped when the caller opted out
+            let search_entry_point = metadata
+                .as_mut()
+                .and_then(|meta| meta.search_entry_point.as_mut())
+                .and_then(|entry| {
+                    entry.rendered_content = entry
+                        .rendered_content
+                        .take()
+                        .filter(|_| include_search_entry_point)
+                        .map(|html| sanitize_search_entry_point(&html));
+                    entry.rendered_content.clone()
+                });
 
             // Debug: log raw response when search is enabled
             if enable_search {
@@ -143,37 +365,198 @@ fn process_candidate<R: Runtime>(
                 }
             }
 
+            let citations = metadata
+                .as_ref()
+                .map(build_citations)
+                .filter(|c| !c.is_empty());
+
             // CRITICAL FIX: Emit if we have EITHER text OR metadata
             if text.is_some() || metadata.is_some() {
                 let _ = app.emit(event_name, StreamPayload {
+                    chat_id: chat_id.to_string(),
                     text,
                     is_done: false,
                     metadata, 
+                    citations,
+                    search_entry_point,
                 });
             }
         }
     }
 }
 
+fn api_key_request(key: Option<String>) -> KeychainRequest {
+    KeychainRequest {
+        key: Some(GEMINI_API_KEY_ENTRY.to_string()),
+        password: key,
+    }
+}
+
+/// The key saved in the keychain; a missing entry is reported as an error by some
+/// platforms, so any failure reads as "no key"
+fn stored_api_key<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    match app.keychain().get_item(api_key_request(None)) {
+        Ok(response) => response.password.filter(|k| !k.is_empty()),
+        Err(e) => {
+            log::debug!("No Gemini API key in the keychain: {}", e);
+            None
+        }
+    }
+}
+
+/// Store the user's Gemini API key in the OS keychain so it doesn't have to be passed on every request
+#[tauri::command]
+pub fn set_gemini_api_key<R: Runtime>(app: AppHandle<R>, key: Option<String>) -> Result<(), String> {
+    match key.map(|k| k.trim().to_string()).filter(|k| !k.is_empty()) {
+        Some(key) => app
+            .keychain()
+            .save_item(api_key_request(Some(key)))
+            .map(|_| ())
+            .map_err(|e| format!("Failed to save Gemini API key to keychain: {}", e)),
+        None => {
+            if stored_api_key(&app).is_none() {
+                return Ok(());
+            }
+            app.keychain()
+                .remove_item(api_key_request(None))
+                .map(|_| ())
+                .map_err(|e| format!("Failed to remove Gemini API key from keychain: {}", e))
+        }
+    }
+}
+
+/// Get the stored Gemini API key, if any
+#[tauri::command]
+pub fn get_gemini_api_key<R: Runtime>(app: AppHandle<R>) -> Result<Option<String>, String> {
+    Ok(stored_api_key(&app))
+}
+
+/// Store a proxy URL for Gemini requests, or clear it to fall back to the proxy env vars
+#[tauri::command]
+pub fn set_http_proxy<R: Runtime>(app: AppHandle<R>, proxy: Option<String>) -> Result<(), String> {
+    match proxy.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        Some(proxy) => {
+            reqwest::Proxy::all(&proxy).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+            settings::set_value(&app, HTTP_PROXY_SETTING, serde_json::Value::String(proxy))
+        }
+        None => settings::delete_key(&app, HTTP_PROXY_SETTING),
+    }
+}
+
+/// Proxy in effect for Gemini requests: the stored setting, else the standard env vars
+fn configured_proxy<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    settings::get_string(app, HTTP_PROXY_SETTING).or_else(|| {
+        ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|v| !v.trim().is_empty()))
+    })
+}
+
+/// Build the HTTP client for Gemini requests
+/// reqwest already honors HTTPS_PROXY/HTTP_PROXY/NO_PROXY; a stored proxy setting takes
+/// precedence and still respects NO_PROXY.
+pub(crate) fn http_client<R: Runtime>(app: &AppHandle<R>) -> Result<Client, String> {
+    let mut builder = Client::builder();
+    if let Some(proxy) = settings::get_string(app, HTTP_PROXY_SETTING) {
+        let proxy = reqwest::Proxy::all(&proxy)
+            .map_err(|e| format!("Invalid proxy URL in settings: {}", e))?
+            .no_proxy(reqwest::NoProxy::from_env());
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+/// Describe a send error, calling out proxy failures so they aren't mistaken for API outages
+pub(crate) fn request_error<R: Runtime>(app: &AppHandle<R>, e: reqwest::Error) -> String {
+    let e = e.without_url();
+    match configured_proxy(app) {
+        Some(proxy) if e.is_connect() => {
+            // Never echo proxy credentials back into error messages
+            let proxy = url::Url::parse(&proxy)
+                .map(|mut url| {
+                    let _ = url.set_password(None);
+                    url.to_string()
+                })
+                .unwrap_or(proxy);
+            format!(
+                "Request failed: could not connect through proxy {} ({}). Check the proxy settings.",
+                proxy, e
+            )
+        }
+        _ => format!("Request failed: {}", e),
+    }
+}
+
+/// Build a Gemini API URL from `path` (e.g. "/v1beta/models") on the base URL in effect:
+/// the explicitly passed one, else GEMINI_BASE_URL, else the public API host.
+/// The base may carry a path prefix for gateways; it must be https with no query or fragment.
+pub(crate) fn gemini_url(base_url: Option<String>, path: &str) -> Result<String, String> {
+    let base = base_url
+        .filter(|u| !u.trim().is_empty())
+        .or_else(|| std::env::var("GEMINI_BASE_URL").ok().filter(|u| !u.trim().is_empty()))
+        .unwrap_or_else(|| DEFAULT_GEMINI_BASE_URL.to_string());
+
+    let parsed = url::Url::parse(base.trim())
+        .map_err(|e| format!("Invalid Gemini base URL '{}': {}", base, e))?;
+    if parsed.scheme() != "https" {
+        return Err(format!("Gemini base URL must use https (got '{}')", base));
+    }
+    if parsed.host_str().is_none() {
+        return Err(format!("Gemini base URL '{}' has no host", base));
+    }
+    if parsed.query().is_some() || parsed.fragment().is_some() {
+        return Err(format!(
+            "Gemini base URL '{}' must not contain a query or fragment",
+            base
+        ));
+    }
+
+    Ok(format!("{}{}", parsed.as_str().trim_end_matches('/'), path))
+}
+
+/// Use the explicitly passed key, falling back to the stored one
+pub(crate) fn resolve_api_key<R: Runtime>(app: &AppHandle<R>, api_key: Option<String>) -> Result<String, String> {
+    api_key
+        .filter(|k| !k.trim().is_empty())
+        .or_else(|| stored_api_key(app))
+        .ok_or_else(|| "No Gemini API key provided. Set one in settings first.".to_string())
+}
+
 #[tauri::command]
 pub async fn stream_gemini_request<R: Runtime>(
     app: AppHandle<R>,
-    api_key: String,
+    api_key: Option<String>,
     prompt: String,
     history: Option<Vec<ChatMessage>>,
     chat_id: String,
     enable_search: Option<bool>,
+    save_to: Option<String>,
+    history_limit: Option<HistoryLimit>,
+    base_url: Option<String>,
+    include_search_entry_point: Option<bool>,
 ) -> Result<(), String> {
-    let client = Client::new();
+    let api_key = resolve_api_key(&app, api_key)?;
+    // On by default: grounded answers must show the search suggestions
+    let include_search_entry_point = include_search_entry_point.unwrap_or(true);
+    // Optional crash-resilient copy of the response, appended chunk by chunk
+    let mut save_file = match save_to.filter(|p| !p.trim().is_empty()) {
+        Some(path) => Some(ResponseFile::open(&path)?),
+        None => None,
+    };
+    let client = http_client(&app)?;
     
     let url = format!(
-        "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-flash:streamGenerateContent?alt=sse&key={}",
+        "{}?alt=sse&key={}",
+        gemini_url(base_url, "/v1beta/models/gemini-2.5-flash:streamGenerateContent")?,
         api_key
     );
 
     let mut contents = Vec::new();
     
     if let Some(hist) = history {
+        let hist = truncate_history(hist, &history_limit.unwrap_or_default());
         for msg in hist {
             let role = match msg.role.as_str() {
                 "user" => "user",
@@ -222,7 +605,7 @@ pub async fn stream_gemini_request<R: Runtime>(
         .json(&payload)
         .send()
         .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        .map_err(|e| request_error(&app, e))?;
 
     if !response.status().is_success() {
         let error_text = response.text().await.unwrap_or_default();
@@ -259,7 +642,7 @@ pub async fn stream_gemini_request<R: Runtime>(
                             // If we were accumulating a previous event, try to parse it first
                             if !current_json.is_empty() {
                                 if let Ok(gemini_data) = serde_json::from_str::<GeminiResponse>(&current_json) {
-                                    process_candidate(&app, &event_name, &gemini_data, enable_search.unwrap_or(false));
+                                    process_candidate(&app, &event_name, &chat_id, &gemini_data, enable_search.unwrap_or(false), include_search_entry_point, save_file.as_mut());
                                 }
                                 current_json.clear();
                             }
@@ -272,7 +655,7 @@ pub async fn stream_gemini_request<R: Runtime>(
                                 // Empty line marks end of SSE event - try to parse accumulated JSON
                                 if !current_json.is_empty() {
                                     if let Ok(gemini_data) = serde_json::from_str::<GeminiResponse>(&current_json) {
-                                        process_candidate(&app, &event_name, &gemini_data, enable_search.unwrap_or(false));
+                                        process_candidate(&app, &event_name, &chat_id, &gemini_data, enable_search.unwrap_or(false), include_search_entry_point, save_file.as_mut());
                                     }
                                     current_json.clear();
                                 }
@@ -289,7 +672,10 @@ pub async fn stream_gemini_request<R: Runtime>(
                 }
             }
             Err(e) => {
-                return Err(format!("Stream error: {}", e));
+                if let Some(file) = save_file.as_mut() {
+                    file.flush();
+                }
+                return Err(format!("Stream error: {}", e.without_url()));
             }
         }
     }
@@ -297,16 +683,265 @@ pub async fn stream_gemini_request<R: Runtime>(
     // Try to parse any remaining JSON
     if !current_json.is_empty() {
         if let Ok(gemini_data) = serde_json::from_str::<GeminiResponse>(&current_json) {
-            process_candidate(&app, &event_name, &gemini_data, enable_search.unwrap_or(false));
+            process_candidate(&app, &event_name, &chat_id, &gemini_data, enable_search.unwrap_or(false), include_search_entry_point, save_file.as_mut());
         }
     }
 
+    if let Some(file) = save_file.as_mut() {
+        file.flush();
+    }
+
     // Emit final done event
     let _ = app.emit(&event_name, StreamPayload {
+        chat_id: chat_id.clone(),
         text: None,
         is_done: true,
         metadata: None,
+        citations: None,
+        search_entry_point: None,
     });
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Send a single non-streaming generateContent request and return the concatenated text
+async fn generate_content<R: Runtime>(
+    app: &AppHandle<R>,
+    api_key: &str,
+    base_url: Option<String>,
+    contents: Vec<Content>,
+) -> Result<String, String> {
+    let url = format!(
+        "{}?key={}",
+        gemini_url(base_url, "/v1beta/models/gemini-2.5-flash:generateContent")?,
+        api_key
+    );
+    let payload = GeminiRequest {
+        contents,
+        tools: vec![],
+    };
+
+    let response = http_client(app)?
+        .post(&url)
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| request_error(app, e))?;
+
+    if !response.status().is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("API Error: {}", error_text));
+    }
+
+    let data: GeminiResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Gemini response: {}", e.without_url()))?;
+
+    let text: String = data
+        .candidates
+        .iter()
+        .flatten()
+        .next()
+        .and_then(|c| c.content.as_ref())
+        .map(|c| c.parts.iter().map(|p| p.text.as_str()).collect())
+        .unwrap_or_default();
+
+    if text.trim().is_empty() {
+        return Err("Gemini returned an empty response".to_string());
+    }
+    Ok(text.trim().to_string())
+}
+
+const SUMMARY_PROMPT: &str = "Summarize the following conversation. Start with a one-paragraph overview, then list the key points, decisions, and any action items as bullet points. Only use information present in the material below.";
+
+/// Summarize a conversation's chat messages and transcript with Gemini and store the result
+/// Updates the existing summary for the conversation if there is one, otherwise creates it.
+#[tauri::command]
+pub async fn summarize_conversation<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, DbState>,
+    api_key: Option<String>,
+    conversation_id: Uuid,
+    base_url: Option<String>,
+) -> Result<Summary, String> {
+    let api_key = resolve_api_key(&app, api_key)?;
+
+    let conversation = database::db_get_conversation_by_id(state.clone(), conversation_id)
+        .await?
+        .ok_or_else(|| format!("Conversation {} not found", conversation_id))?;
+    let messages = database::db_get_conversation_messages(state.clone(), conversation_id).await?;
+    let segments =
+        database::db_get_transcription_segments_by_conversation_id(state.clone(), conversation_id)
+            .await?;
+
+    if messages.is_empty() && segments.is_empty() {
+        return Err("Conversation has no messages or transcript to summarize".to_string());
+    }
+
+    let mut material = String::new();
+    if !segments.is_empty() {
+        material.push_str("Transcript:\n");
+        for segment in &segments {
+            material.push_str(segment.text.trim());
+            material.push('\n');
+        }
+        material.push('\n');
+    }
+    if !messages.is_empty() {
+        material.push_str("Chat messages:\n");
+        for message in &messages {
+            let speaker = if message.role == "assistant" { "Assistant" } else { "User" };
+            material.push_str(&format!("{}: {}\n", speaker, message.content.trim()));
+        }
+    }
+
+    let contents = vec![Content {
+        role: Some("user".to_string()),
+        parts: vec![Part {
+            text: format!("{}\n\n{}", SUMMARY_PROMPT, material),
+        }],
+    }];
+    let summary_text = generate_content(&app, &api_key, base_url, contents).await?;
+
+    let existing = database::db_get_summary_by_conversation_id(state.clone(), conversation_id).await?;
+    match existing {
+        Some(summary) => {
+            database::db_update_summary(
+                state,
+                UpdateSummaryInput {
+                    summary_id: summary.id,
+                    title: None,
+                    content: Some(summary_text),
+                },
+            )
+            .await
+        }
+        None => {
+            database::db_create_summary(
+                state,
+                CreateSummaryInput {
+                    conversation_id: Some(conversation_id),
+                    user_id: conversation.user_id,
+                    title: conversation.title,
+                    content: Some(summary_text),
+                },
+            )
+            .await
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct KeyValidation {
+    pub valid: bool,
+    pub model_count: Option<usize>,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ModelList {
+    #[serde(default)]
+    models: Vec<serde_json::Value>,
+}
+
+/// Check a Gemini API key by listing the models it can access
+/// An invalid key is reported in the result rather than as an Err; Err is reserved for
+/// failures to reach the API at all (network, proxy).
+#[tauri::command]
+pub async fn validate_gemini_key<R: Runtime>(
+    app: AppHandle<R>,
+    api_key: String,
+    base_url: Option<String>,
+) -> Result<KeyValidation, String> {
+    let api_key = api_key.trim();
+    if api_key.is_empty() {
+        return Ok(KeyValidation {
+            valid: false,
+            model_count: None,
+            error: Some("API key is empty".to_string()),
+        });
+    }
+
+    let models_url = gemini_url(base_url, "/v1beta/models")?;
+    let response = http_client(&app)?
+        .get(&models_url)
+        .query(&[("key", api_key)])
+        .send()
+        .await
+        .map_err(|e| request_error(&app, e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        let message = body["error"]["message"]
+            .as_str()
+            .map(|m| m.to_string())
+            .unwrap_or_else(|| format!("API returned {}", status));
+        return Ok(KeyValidation {
+            valid: false,
+            model_count: None,
+            error: Some(message),
+        });
+    }
+
+    let models: ModelList = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Gemini response: {}", e.without_url()))?;
+
+    Ok(KeyValidation {
+        valid: true,
+        model_count: Some(models.models.len()),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_search_entry_point_drops_scripts_and_styles() {
+        let html = r#"<style>.chip { color: red }</style><div class="container" style="color: red"><script>alert(1)</script>Suggestions</div>"#;
+        let clean = sanitize_search_entry_point(html);
+        assert!(!clean.contains("script"));
+        assert!(!clean.contains("alert"));
+        assert!(!clean.contains("style"));
+        assert!(!clean.contains("color"));
+        assert!(clean.contains(r#"<div class="container">Suggestions</div>"#));
+    }
+
+    #[test]
+    fn sanitize_search_entry_point_drops_event_handlers() {
+        let clean = sanitize_search_entry_point(r#"<div onclick="alert(1)" onmouseover="x()">chip</div>"#);
+        assert_eq!(clean, "<div>chip</div>");
+    }
+
+    #[test]
+    fn sanitize_search_entry_point_keeps_only_http_links() {
+        let clean = sanitize_search_entry_point(
+            r#"<a href="javascript:alert(1)">a</a><a href="https://www.google.com/search?q=rust">b</a>"#,
+        );
+        assert!(!clean.contains("javascript"));
+        assert!(clean.contains(r#"href="https://www.google.com/search?q=rust""#));
+        assert!(clean.contains(r#"rel="noopener noreferrer""#));
+    }
+
+    #[test]
+    fn sanitize_search_entry_point_drops_svg_animation() {
+        let html = r#"<svg viewBox="0 0 24 24"><animate attributeName="href" values="javascript:alert(1)"/><set attributeName="href" to="javascript:alert(1)"/><path d="M0 0h24v24H0z"/></svg>"#;
+        let clean = sanitize_search_entry_point(html);
+        assert!(!clean.contains("animate"));
+        assert!(!clean.contains("<set"));
+        assert!(!clean.contains("javascript"));
+        assert!(clean.contains("<svg"));
+        assert!(clean.contains(r#"d="M0 0h24v24H0z""#));
+    }
+
+    #[test]
+    fn sanitize_search_entry_point_strips_comments() {
+        let clean = sanitize_search_entry_point("<div><!-- <script>alert(1)</script> -->chip</div>");
+        assert_eq!(clean, "<div>chip</div>");
+    }
+}
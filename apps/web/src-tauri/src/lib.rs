@@ -9,14 +9,27 @@ use tauri_plugin_shell;
 use tokio::task::JoinHandle;
 mod gemini;
 // === Modules ===
+mod analytics;
 mod audio_utils;
+mod autostart;
+mod backup;
 mod capture;
+mod captions;
 mod database;
+mod embeddings;
 mod login;
+mod mixed_recording;
+mod model_download;
+mod paths;
+mod permissions;
 mod realtime_transcription;
+mod remote_transcription;
+mod settings;
 mod shortcuts;
+mod shutdown;
 mod system_audio_transcription;
 mod transcription;
+mod updates;
 mod window;
 
 // === UPDATED IMPORT HERE ===
@@ -28,7 +41,7 @@ use capture::CaptureState;
 pub use login::{login_with_provider, UserInfo};
 use realtime_transcription::{start_transcription, stop_transcription, RealtimeState};
 use system_audio_transcription::{
-    start_system_audio_recording, start_system_audio_transcription,
+    cancel_system_audio_recording, start_system_audio_recording, start_system_audio_transcription,
     stop_system_audio_recording_and_transcribe, stop_system_audio_transcription,
     SystemAudioRecordingState, SystemAudioTranscriptionState,
 };
@@ -40,21 +53,132 @@ pub struct AudioState {
     is_capturing: Arc<Mutex<bool>>,
 }
 
+/// The OAuth redirect listener currently bound, if any
+#[derive(Default)]
+pub struct OAuthServerState {
+    server: Arc<Mutex<Option<OAuthServer>>>,
+}
+
+struct OAuthServer {
+    port: u16,
+    /// Distinguishes this listener from a later one bound to the same port
+    started_at: std::time::Instant,
+    /// Whether the redirect has arrived (the timeout then closes it without `oauth_timeout`)
+    redirected: bool,
+}
+
+/// Name the app registers itself under (the autostart entry) and identifies itself with to
+/// other services. Set `BANGG_APP_NAME` at build time to rename a build; an autostart entry
+/// made under a previous name stays registered until the user turns it off.
+pub(crate) const APP_NAME: &str = match option_env!("BANGG_APP_NAME") {
+    Some(name) => name,
+    None => "Bangg",
+};
+
 #[tauri::command]
 fn get_app_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
+const DEFAULT_OAUTH_PORTS: [u16; 3] = [8000, 8001, 8002];
+const DEFAULT_OAUTH_SUCCESS_MESSAGE: &str = "Login successful. You can close this window.";
+const DEFAULT_OAUTH_TIMEOUT_SECS: u64 = 300;
+
+#[derive(Serialize, Clone)]
+struct OAuthTimeoutPayload {
+    port: u16,
+}
+
+/// Stop the redirect listener and release its port
+fn stop_oauth_listener(server: OAuthServer) {
+    match tauri_plugin_oauth::cancel(server.port) {
+        Ok(()) => log::info!("OAuth redirect listener on port {} closed", server.port),
+        Err(e) => log::warn!(
+            "Failed to close OAuth redirect listener on port {}: {}",
+            server.port,
+            e
+        ),
+    }
+}
+
+/// Start the OAuth redirect listener and return the port it actually bound to
+/// The listener closes after `timeout_secs` (default 300), emitting `oauth_timeout` if no
+/// redirect arrived. Starting a new listener closes any previous one.
 #[tauri::command]
-fn start_oauth_server(window: Window) -> Result<u16, String> {
+fn start_oauth_server(
+    window: Window,
+    state: tauri::State<'_, OAuthServerState>,
+    ports: Option<Vec<u16>>,
+    success_message: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<u16, String> {
+    let timeout_secs = timeout_secs.unwrap_or(DEFAULT_OAUTH_TIMEOUT_SECS);
+    if timeout_secs == 0 {
+        return Err("timeout_secs must be greater than zero".to_string());
+    }
+    if let Some(previous) = state.server.lock().unwrap().take() {
+        stop_oauth_listener(previous);
+    }
+
+    let ports = ports
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| DEFAULT_OAUTH_PORTS.to_vec());
+    let response = success_message
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_OAUTH_SUCCESS_MESSAGE.to_string());
+
     let config = OauthConfig {
-        ports: Some(vec![8000, 8001, 8002]),
-        response: Some("Login successful. You can close this window.".into()),
+        ports: Some(ports),
+        response: Some(response.into()),
     };
-    tauri_plugin_oauth::start_with_config(config, move |url| {
-        let _ = window.emit("oauth_redirect", url);
+    let started_at = std::time::Instant::now();
+    let redirect_window = window.clone();
+    let redirect_server = state.server.clone();
+    let port = tauri_plugin_oauth::start_with_config(config, move |url| {
+        if let Some(server) = redirect_server.lock().unwrap().as_mut() {
+            if server.started_at == started_at {
+                server.redirected = true;
+            }
+        }
+        let _ = redirect_window.emit("oauth_redirect", url);
     })
-    .map_err(|err| err.to_string())
+    .map_err(|err| err.to_string())?;
+
+    *state.server.lock().unwrap() = Some(OAuthServer {
+        port,
+        started_at,
+        redirected: false,
+    });
+
+    let server = state.server.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(timeout_secs)).await;
+        let expired = {
+            let mut guard = server.lock().unwrap();
+            match guard.as_ref() {
+                Some(current) if current.started_at == started_at => guard.take(),
+                _ => None,
+            }
+        };
+        if let Some(expired) = expired {
+            if !expired.redirected {
+                log::warn!("OAuth login timed out after {}s", timeout_secs);
+                let _ = window.emit("oauth_timeout", OAuthTimeoutPayload { port });
+            }
+            stop_oauth_listener(expired);
+        }
+    });
+
+    Ok(port)
+}
+
+/// Close the OAuth redirect listener (e.g. when the user abandons login)
+#[tauri::command]
+fn cancel_oauth_server(state: tauri::State<'_, OAuthServerState>) -> Result<(), String> {
+    if let Some(server) = state.server.lock().unwrap().take() {
+        stop_oauth_listener(server);
+    }
+    Ok(())
 }
 
 #[derive(Serialize, Clone)]
@@ -85,6 +209,11 @@ fn show_menu_window_and_emit(app: tauri::AppHandle, chat_id: String) -> Result<(
     Ok(())
 }
 
+#[derive(Serialize, Clone)]
+struct MenuVisibilityPayload {
+    visible: bool,
+}
+
 #[tauri::command]
 fn show_menu_window(app: tauri::AppHandle) -> Result<(), String> {
     let menu_window = app
@@ -98,12 +227,49 @@ fn show_menu_window(app: tauri::AppHandle) -> Result<(), String> {
         .set_focus()
         .map_err(|e| format!("Failed to focus menu window: {}", e))?;
 
+    let _ = app.emit("menu_visibility", MenuVisibilityPayload { visible: true });
+
     Ok(())
 }
 
+#[tauri::command]
+fn hide_menu_window(app: tauri::AppHandle) -> Result<(), String> {
+    let menu_window = app
+        .get_webview_window("menu")
+        .ok_or("Menu window not found")?;
+
+    menu_window
+        .hide()
+        .map_err(|e| format!("Failed to hide menu window: {}", e))?;
+
+    let _ = app.emit("menu_visibility", MenuVisibilityPayload { visible: false });
+
+    Ok(())
+}
+
+/// Show + focus the menu window if hidden, hide it if visible. Returns the new visibility.
+#[tauri::command]
+fn toggle_menu_window(app: tauri::AppHandle) -> Result<bool, String> {
+    let menu_window = app
+        .get_webview_window("menu")
+        .ok_or("Menu window not found")?;
+
+    let is_visible = menu_window
+        .is_visible()
+        .map_err(|e| format!("Failed to check menu window visibility: {}", e))?;
+
+    if is_visible {
+        hide_menu_window(app)?;
+    } else {
+        show_menu_window(app)?;
+    }
+
+    Ok(!is_visible)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let posthog_api_key = option_env!("POSTHOG_API_KEY").unwrap_or("").to_string();
+    let posthog_api_key = analytics::posthog_api_key();
 
     tauri::Builder::default()
         // === Plugins ===
@@ -113,8 +279,8 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .plugin(
             tauri_plugin_autostart::Builder::new()
-                .app_name("AI-Overlay")
-                .args(["--flag1"])
+                .app_name(APP_NAME)
+                .args([autostart::AUTOSTART_ARG])
                 .build(),
         )
         .plugin(tauri_plugin_updater::Builder::new().build())
@@ -134,15 +300,21 @@ pub fn run() {
         }))
         // === States ===
         .manage(AudioState::default())
+        .manage(OAuthServerState::default())
         .manage(CaptureState::default())
         .manage(transcription::TranscriptionState::default())
         .manage(RealtimeState::default())
         .manage(SystemAudioTranscriptionState::default())
         .manage(SystemAudioRecordingState::default())
         .manage(shortcuts::RegisteredShortcuts::default())
+        .manage(captions::CaptionServerState::default())
+        .manage(model_download::ModelDownloadState::default())
+        .manage(database::ConnectivityMonitor::default())
         .setup(|app| {
             let app_handle = app.handle().clone();
 
+            autostart::sync_autostart_preference(&app_handle);
+
             // === 1. Setup Logic for "menu" Window ===
             if let Some(menu_window) = app.get_webview_window("menu") {
                 #[cfg(target_os = "macos")]
@@ -186,16 +358,29 @@ pub fn run() {
             );
 
             log::info!("✓ Database pool created successfully");
-            app.manage(database::DbState { pool });
+            app.manage(database::DbState::new(pool));
             log::info!("✓ DbState managed successfully");
+            app.state::<database::ConnectivityMonitor>()
+                .start(app_handle.clone());
 
             Ok(())
         })
         // === Commands ===
         .invoke_handler(tauri::generate_handler![
             start_oauth_server,
+            cancel_oauth_server,
+            autostart::set_autostart,
+            autostart::is_autostart_enabled,
+            autostart::was_autostarted,
+            updates::check_for_update,
+            updates::install_update,
+            analytics::track_event,
+            captions::start_caption_server,
+            captions::stop_caption_server,
             show_menu_window_and_emit,
             show_menu_window,
+            hide_menu_window,
+            toggle_menu_window,
             login::login_with_provider,
             capture::capture_to_base64,
             capture::start_screen_capture,
@@ -203,23 +388,56 @@ pub fn run() {
             capture::close_overlay_window,
             window::set_window_height,
             transcription::initialize_whisper,
+            transcription::initialize_whisper_from_bytes,
+            transcription::debug_model_resolution,
             transcription::transcribe_audio,
             transcription::transcribe_audio_with_timestamps,
+            transcription::transcribe_audio_tokens,
+            transcription::retranscribe_file,
+            transcription::transcribe_audio_local_with_timestamps,
+            transcription::coalesce_segments,
+            transcription::resegment,
+            transcription::transcript_stats,
+            transcription::diff_transcripts,
+            model_download::download_model,
+            model_download::download_models,
+            model_download::cancel_model_download,
+            model_download::cancel_all_model_downloads,
+            model_download::get_bundled_model_info,
             transcription::check_whisper_status,
+            transcription::list_loaded_models,
+            transcription::set_active_model,
             transcription::get_model_paths,
             transcription::get_model_path,
             start_transcription,
             stop_transcription,
+            realtime_transcription::get_recent_transcription,
+            permissions::check_audio_permissions,
+            permissions::request_audio_permissions,
             start_system_audio_transcription,
             stop_system_audio_transcription,
             start_system_audio_recording,
             stop_system_audio_recording_and_transcribe,
+            cancel_system_audio_recording,
+            system_audio_transcription::list_render_devices,
+            mixed_recording::start_mixed_recording,
             audio_utils::save_audio_buffer,
+            audio_utils::pcm_to_wav,
+            audio_utils::probe_audio,
+            audio_utils::analyze_audio_levels,
+            audio_utils::classify_audio,
+            audio_utils::trim_audio,
+            audio_utils::convert_to_16khz_wav,
+            audio_utils::split_audio,
             audio_utils::list_audio_files,
+            paths::get_app_paths,
             database::db_get_conversations,
+            database::db_get_conversation_types,
+            database::db_get_conversations_grouped,
             database::db_get_conversation_by_id,
             database::db_update_conversation,
             database::db_delete_conversation,
+            database::db_purge_old_conversations,
             database::db_get_conversation_messages,
             database::db_create_conversation_message,
             database::db_get_chats,
@@ -227,19 +445,47 @@ pub fn run() {
             database::db_create_chat,
             database::db_update_chat,
             database::db_get_chat_by_conversation_id,
+            database::transcription_to_chat,
             database::db_get_messages,
+            database::db_get_messages_paged,
             database::db_delete_message,
             database::db_get_summary_by_conversation_id,
             database::db_create_summary,
             database::db_update_summary,
+            gemini::summarize_conversation,
             database::db_get_transcriptions,
             database::db_get_transcription_by_id,
+            database::db_update_transcription,
+            database::db_delete_transcription,
             database::db_get_transcription_segments,
             database::db_create_transcription_segment,
             database::db_get_transcription_segments_by_conversation_id,
+            database::db_get_transcript_text,
+            database::db_find_orphans,
+            database::db_repair_orphans,
             database::db_test_connection,
+            database::set_database_url,
+            database::db_reconnect,
+            backup::export_database,
+            backup::import_database,
+            database::db_pool_stats,
+            database::db_count,
+            database::db_check_schema,
+            embeddings::db_generate_embeddings,
+            embeddings::db_semantic_search,
             gemini::stream_gemini_request,
+            remote_transcription::transcribe_remote,
+            gemini::set_gemini_api_key,
+            gemini::get_gemini_api_key,
+            gemini::set_http_proxy,
+            gemini::validate_gemini_key,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                shutdown::stop_capture_threads(app);
+                app.state::<database::ConnectivityMonitor>().stop();
+            }
+        });
 }
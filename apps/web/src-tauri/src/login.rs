@@ -132,7 +132,7 @@ pub async fn login_with_provider(app: tauri::AppHandle, provider: String) -> Res
                 .get(&config.user_info_url)
                 .header("Authorization", format!("Bearer {}", access_token))
                 .header("Accept", "application/vnd.github.v3+json")
-                .header("User-Agent", "tauri-app")
+                .header("User-Agent", crate::APP_NAME)
                 .send()
                 .await
         }
@@ -0,0 +1,275 @@
+// Meeting capture: microphone and system loopback recorded together as one mono 16kHz mix.
+// The mix goes into the regular system audio recording buffer, so it is finished with
+// stop_system_audio_recording_and_transcribe (or discarded with cancel_system_audio_recording).
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::audio_utils::resample_audio;
+use crate::system_audio_transcription::SystemAudioRecordingState;
+
+/// Rate both sources are converted to before mixing (what Whisper expects)
+const MIX_SAMPLE_RATE: u32 = 16000;
+/// How far (200ms) one source may run ahead before the other is assumed silent. Loopback
+/// delivers no packets at all while nothing is playing, so without this the mix would stall.
+const MAX_LAG_SAMPLES: usize = MIX_SAMPLE_RATE as usize / 5;
+const MAX_GAIN: f32 = 4.0;
+
+#[derive(Clone, Copy, Debug)]
+struct MixGains {
+    mic: f32,
+    system: f32,
+}
+
+/// Which sources of a mixed capture feed Whisper; both are always recorded into the saved
+/// audio. Defaults to the microphone only ("transcribe what I say, record what they say").
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscribeSources {
+    pub mic: bool,
+    pub system: bool,
+}
+
+impl Default for TranscribeSources {
+    fn default() -> Self {
+        TranscribeSources {
+            mic: true,
+            system: false,
+        }
+    }
+}
+
+impl TranscribeSources {
+    fn both(&self) -> bool {
+        self.mic && self.system
+    }
+}
+
+fn validate_gain(name: &str, gain: Option<f32>) -> Result<f32, String> {
+    let gain = gain.unwrap_or(1.0);
+    if !gain.is_finite() || !(0.0..=MAX_GAIN).contains(&gain) {
+        return Err(format!("{} must be between 0 and {} (got {})", name, MAX_GAIN, gain));
+    }
+    Ok(gain)
+}
+
+/// Start recording the default microphone and system audio mixed together
+/// `mic_gain` / `system_gain` scale each source before summing (default 1.0, max 4.0);
+/// `device_id` picks the output device to capture, as in `start_system_audio_recording`.
+/// `transcribe_sources` picks what the stop command transcribes (default the mic only);
+/// the saved recording always has both.
+#[tauri::command]
+pub async fn start_mixed_recording(
+    app: AppHandle,
+    state: State<'_, SystemAudioRecordingState>,
+    mic_gain: Option<f32>,
+    system_gain: Option<f32>,
+    device_id: Option<String>,
+    transcribe_sources: Option<TranscribeSources>,
+) -> Result<(), String> {
+    let gains = MixGains {
+        mic: validate_gain("mic_gain", mic_gain)?,
+        system: validate_gain("system_gain", system_gain)?,
+    };
+    let sources = transcribe_sources.unwrap_or_default();
+    if !sources.mic && !sources.system {
+        return Err("transcribe_sources must include the mic or system audio".into());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    return Err("System audio recording only supported on Windows currently".into());
+
+    let mut recording = state.recording.lock().unwrap();
+    if *recording {
+        return Err("Recording already in progress".into());
+    }
+    *recording = true;
+    drop(recording);
+
+    // Clear previous recording; the mix is always produced at MIX_SAMPLE_RATE
+    state.audio_buffer.lock().unwrap().clear();
+    *state.sample_rate.lock().unwrap() = Some(MIX_SAMPLE_RATE);
+    // Transcribing everything is just the full mix, so only a partial choice needs its own buffer
+    *state.transcription_buffer.lock().unwrap() = if sources.both() {
+        None
+    } else {
+        Some(Vec::new())
+    };
+
+    let recording_clone = state.recording.clone();
+    let buffer_clone = state.audio_buffer.clone();
+    let transcription_clone = state.transcription_buffer.clone();
+
+    // Streams are created inside the thread - neither cpal nor WASAPI handles are Send
+    #[cfg(target_os = "windows")]
+    let worker = thread::spawn(move || {
+        if let Err(e) = record_mixed_audio(
+            recording_clone.clone(),
+            buffer_clone,
+            transcription_clone,
+            device_id,
+            gains,
+            sources,
+        ) {
+            log::error!("Mixed recording failed: {:?}", e);
+            *recording_clone.lock().unwrap() = false;
+            let _ = app.emit("transcription_error", e.to_string());
+        }
+    });
+    #[cfg(target_os = "windows")]
+    {
+        *state.worker.lock().unwrap() = Some(worker);
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn record_mixed_audio(
+    recording: Arc<Mutex<bool>>,
+    audio_buffer: Arc<Mutex<Vec<f32>>>,
+    transcription_buffer: Arc<Mutex<Option<Vec<f32>>>>,
+    device_id: Option<String>,
+    gains: MixGains,
+    sources: TranscribeSources,
+) -> Result<()> {
+    use cpal::traits::HostTrait;
+
+    use crate::realtime_transcription::open_microphone_stream;
+    use crate::system_audio_transcription::{open_loopback_capture, ReadFailures};
+
+    let mic_device = cpal::default_host()
+        .default_input_device()
+        .ok_or_else(|| anyhow::anyhow!("Microphone unavailable: no input device found"))?;
+    let mic_buffer = Arc::new(Mutex::new(Vec::<f32>::new()));
+    let (mic_stream, mic_config) = open_microphone_stream(&mic_device, mic_buffer.clone())?;
+    let mic_rate = mic_config.sample_rate().0;
+    let mic_channels = mic_config.channels() as usize;
+
+    let mut capture = open_loopback_capture(device_id.as_deref())?;
+    let system_rate = capture.sample_rate;
+    let mut failures = ReadFailures::default();
+
+    log::info!(
+        "Mixed recording started: mic {}Hz/{}ch, system {}Hz {}, gains {:?}, transcribing {:?}",
+        mic_rate,
+        mic_channels,
+        system_rate,
+        capture.format.label(),
+        gains,
+        sources
+    );
+
+    let mut system_bytes = VecDeque::new();
+    let mut mic_pending = VecDeque::new();
+    let mut system_pending = VecDeque::new();
+
+    while *recording.lock().unwrap() {
+        // The timeout also paces the loop while the system is silent and sends nothing
+        if capture.event_handle.wait_for_event(100).is_ok() {
+            match capture.capture_client.read_from_device_to_deque(&mut system_bytes) {
+                Ok(()) => {
+                    failures.reset();
+                    let system = capture.format.drain_samples(&mut system_bytes);
+                    system_pending.extend(to_mix_rate(&system, system_rate));
+                }
+                Err(e) => {
+                    if let Some(reopened) = failures.record(e, device_id.as_deref(), system_rate)? {
+                        // Bytes of a half-read frame belong to the old stream
+                        system_bytes.clear();
+                        capture = reopened;
+                    }
+                }
+            }
+        }
+
+        let mic = std::mem::take(&mut *mic_buffer.lock().unwrap());
+        mic_pending.extend(to_mix_rate(&downmix(&mic, mic_channels), mic_rate));
+
+        let aligned = take_aligned(&mut mic_pending, &mut system_pending, MAX_LAG_SAMPLES);
+        store_mix(&aligned, gains, sources, &audio_buffer, &transcription_buffer);
+    }
+
+    drop(mic_stream);
+
+    // Whatever is left over is mixed against silence
+    let rest = take_aligned(&mut mic_pending, &mut system_pending, 0);
+    store_mix(&rest, gains, sources, &audio_buffer, &transcription_buffer);
+
+    log::info!("Mixed recording stopped");
+    Ok(())
+}
+
+/// Average interleaved frames down to mono
+fn downmix(samples: &[f32], channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+fn to_mix_rate(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    if sample_rate == MIX_SAMPLE_RATE || samples.is_empty() {
+        samples.to_vec()
+    } else {
+        resample_audio(samples, sample_rate, MIX_SAMPLE_RATE)
+    }
+}
+
+/// Take the `(mic, system)` sample pairs both sources have in common, leaving the longer
+/// source's extra samples queued for the next pass. If one source is more than `max_lag`
+/// samples behind, it is padded with silence so the other isn't held back indefinitely.
+fn take_aligned(
+    mic: &mut VecDeque<f32>,
+    system: &mut VecDeque<f32>,
+    max_lag: usize,
+) -> Vec<(f32, f32)> {
+    if mic.len().abs_diff(system.len()) > max_lag {
+        let target = mic.len().max(system.len());
+        mic.resize(target, 0.0);
+        system.resize(target, 0.0);
+    }
+
+    let count = mic.len().min(system.len());
+    mic.drain(..count).zip(system.drain(..count)).collect()
+}
+
+/// Sum aligned pairs with their gains, leaving out the sources switched off in `include`
+fn mix(aligned: &[(f32, f32)], gains: MixGains, include: TranscribeSources) -> Vec<f32> {
+    let mic_gain = if include.mic { gains.mic } else { 0.0 };
+    let system_gain = if include.system { gains.system } else { 0.0 };
+    aligned
+        .iter()
+        .map(|&(m, s)| (m * mic_gain + s * system_gain).clamp(-1.0, 1.0))
+        .collect()
+}
+
+/// Append the full mix to the recording and, when only some sources are transcribed, their
+/// mix to the transcription buffer
+fn store_mix(
+    aligned: &[(f32, f32)],
+    gains: MixGains,
+    sources: TranscribeSources,
+    audio_buffer: &Mutex<Vec<f32>>,
+    transcription_buffer: &Mutex<Option<Vec<f32>>>,
+) {
+    if aligned.is_empty() {
+        return;
+    }
+    let everything = TranscribeSources {
+        mic: true,
+        system: true,
+    };
+    audio_buffer.lock().unwrap().extend(mix(aligned, gains, everything));
+    if let Some(transcribed) = transcription_buffer.lock().unwrap().as_mut() {
+        transcribed.extend(mix(aligned, gains, sources));
+    }
+}
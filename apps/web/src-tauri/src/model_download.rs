@@ -0,0 +1,415 @@
+// Whisper model downloads into the app data models folder, which resolve_model_path prefers
+// over the bundled copy when the downloaded file is newer
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures_util::StreamExt;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::AsyncWriteExt;
+
+const DEFAULT_MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+/// Downloads `download_models` runs at once unless told otherwise
+const DEFAULT_CONCURRENT_DOWNLOADS: usize = 2;
+const MAX_CONCURRENT_DOWNLOADS: usize = 4;
+
+/// Cancellation flags for in-flight downloads, keyed by model name
+#[derive(Default)]
+pub struct ModelDownloadState {
+    downloads: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+#[derive(Serialize, Clone)]
+struct ModelDownloadProgress {
+    model_name: String,
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+#[derive(Serialize, Clone)]
+struct ModelDownloadCancelled {
+    model_name: String,
+}
+
+/// How one model of a `download_models` batch ended; also emitted as `model_download_finished`
+#[derive(Serialize, Clone, Debug)]
+pub struct ModelDownloadOutcome {
+    pub model_name: String,
+    /// Where the model was saved, if it downloaded
+    pub path: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Only plain ggml model file names are accepted, so a name can't escape the models folder
+fn validate_model_name(model_name: &str) -> Result<(), String> {
+    let valid = model_name.ends_with(".bin")
+        && !model_name.contains(['/', '\\'])
+        && !model_name.starts_with('.');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid model name: {}", model_name))
+    }
+}
+
+/// Download a Whisper model, emitting `model_download_progress` as bytes arrive
+/// The file is written to `<name>.part` and only renamed into place once complete. An
+/// interrupted download leaves the `.part` file behind and the next attempt resumes it with
+/// an HTTP range request; if `sha256` is given the finished file must match it.
+#[tauri::command]
+pub async fn download_model(
+    app: AppHandle,
+    state: State<'_, ModelDownloadState>,
+    model_name: String,
+    url: Option<String>,
+    sha256: Option<String>,
+) -> Result<String, String> {
+    validate_model_name(&model_name)?;
+    let cancelled = register_download(&state, &model_name)?;
+
+    let result = stream_model_to_disk(&app, &model_name, url, sha256, &cancelled).await;
+    state.downloads.lock().unwrap().remove(&model_name);
+    result
+}
+
+/// Claim `model_name` for a new download and return its cancellation flag
+/// A fresh flag per download, so a previous cancel never affects a new attempt.
+fn register_download(state: &ModelDownloadState, model_name: &str) -> Result<Arc<AtomicBool>, String> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let mut downloads = state.downloads.lock().unwrap();
+    if downloads.contains_key(model_name) {
+        return Err(format!("{} is already downloading", model_name));
+    }
+    downloads.insert(model_name.to_string(), cancelled.clone());
+    Ok(cancelled)
+}
+
+/// Download several models from the default source, `max_concurrent` at a time (default 2,
+/// max 4), e.g. for first-run setup
+/// Each model reports `model_download_progress` like `download_model` and a
+/// `model_download_finished` outcome when it ends. Queued models are registered up front,
+/// so `cancel_model_download` / `cancel_all_model_downloads` also drop ones not yet started.
+/// One model failing doesn't stop the others; every outcome is returned in request order.
+#[tauri::command]
+pub async fn download_models(
+    app: AppHandle,
+    state: State<'_, ModelDownloadState>,
+    model_names: Vec<String>,
+    max_concurrent: Option<usize>,
+) -> Result<Vec<ModelDownloadOutcome>, String> {
+    let max_concurrent = max_concurrent.unwrap_or(DEFAULT_CONCURRENT_DOWNLOADS);
+    if !(1..=MAX_CONCURRENT_DOWNLOADS).contains(&max_concurrent) {
+        return Err(format!(
+            "max_concurrent must be between 1 and {} (got {})",
+            MAX_CONCURRENT_DOWNLOADS, max_concurrent
+        ));
+    }
+
+    let mut unique_names: Vec<String> = Vec::new();
+    for model_name in model_names {
+        validate_model_name(&model_name)?;
+        if !unique_names.contains(&model_name) {
+            unique_names.push(model_name);
+        }
+    }
+    if unique_names.is_empty() {
+        return Err("No models to download".to_string());
+    }
+
+    // Claim every name before starting, so the whole queue is visible to cancellation
+    let queue: Vec<(String, Result<Arc<AtomicBool>, String>)> = unique_names
+        .into_iter()
+        .map(|model_name| {
+            let cancelled = register_download(&state, &model_name);
+            (model_name, cancelled)
+        })
+        .collect();
+
+    log::info!(
+        "Queued {} model download(s), {} at a time",
+        queue.len(),
+        max_concurrent
+    );
+
+    let app = &app;
+    let state = &state;
+    let mut outcomes: Vec<(usize, ModelDownloadOutcome)> = futures_util::stream::iter(
+        queue.into_iter().enumerate(),
+    )
+    .map(|(index, (model_name, cancelled))| async move {
+        let result = match cancelled {
+            Ok(cancelled) => {
+                let result = if cancelled.load(Ordering::SeqCst) {
+                    log::info!("Model download {} cancelled before it started", model_name);
+                    let _ = app.emit(
+                        "model_download_cancelled",
+                        ModelDownloadCancelled {
+                            model_name: model_name.clone(),
+                        },
+                    );
+                    Err("Download cancelled".to_string())
+                } else {
+                    stream_model_to_disk(app, &model_name, None, None, &cancelled).await
+                };
+                state.downloads.lock().unwrap().remove(&model_name);
+                result
+            }
+            // Already downloading elsewhere - leave that download's registration alone
+            Err(e) => Err(e),
+        };
+
+        let outcome = match result {
+            Ok(path) => ModelDownloadOutcome {
+                model_name,
+                path: Some(path),
+                error: None,
+            },
+            Err(error) => {
+                log::warn!("Model download {} failed: {}", model_name, error);
+                ModelDownloadOutcome {
+                    model_name,
+                    path: None,
+                    error: Some(error),
+                }
+            }
+        };
+        let _ = app.emit("model_download_finished", outcome.clone());
+        (index, outcome)
+    })
+    .buffer_unordered(max_concurrent)
+    .collect()
+    .await;
+
+    outcomes.sort_by_key(|(index, _)| *index);
+    Ok(outcomes.into_iter().map(|(_, outcome)| outcome).collect())
+}
+
+/// Total size from a `Content-Range: bytes start-end/total` header
+fn content_range_total(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+async fn sha256_file(path: &Path) -> Result<String, String> {
+    let path = path.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut file = std::fs::File::open(&path)
+            .map_err(|e| format!("Failed to open model file for verification: {}", e))?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)
+            .map_err(|e| format!("Failed to read model file for verification: {}", e))?;
+        Ok(format!("{:x}", hasher.finalize()))
+    })
+    .await
+    .map_err(|e| format!("Model verification task failed: {}", e))?
+}
+
+async fn stream_model_to_disk(
+    app: &AppHandle,
+    model_name: &str,
+    url: Option<String>,
+    sha256: Option<String>,
+    cancelled: &AtomicBool,
+) -> Result<String, String> {
+    let models_dir = crate::paths::models_dir(app)?;
+    tokio::fs::create_dir_all(&models_dir)
+        .await
+        .map_err(|e| format!("Failed to create models directory: {}", e))?;
+
+    let final_path = models_dir.join(model_name);
+    let partial_path = models_dir.join(format!("{}.part", model_name));
+    let url = url.unwrap_or_else(|| format!("{}/{}", DEFAULT_MODEL_BASE_URL, model_name));
+
+    // Resume from whatever a previous attempt left behind
+    let existing = tokio::fs::metadata(&partial_path)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    let client = reqwest::Client::new();
+    let mut request = client.get(&url);
+    if existing > 0 {
+        log::info!("Resuming model {} from byte {} ({})", model_name, existing, url);
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing));
+    } else {
+        log::info!("Downloading model {} from {}", model_name, url);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to start model download: {}", e))?;
+
+    let (mut downloaded, total, mut file) = match response.status() {
+        reqwest::StatusCode::PARTIAL_CONTENT if existing > 0 => {
+            let total = content_range_total(&response)
+                .or_else(|| response.content_length().map(|len| existing + len));
+            let file = tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(&partial_path)
+                .await
+                .map_err(|e| format!("Failed to open partial model file: {}", e))?;
+            (existing, total, file)
+        }
+        status if status.is_success() => {
+            if existing > 0 {
+                log::info!("Server doesn't support resuming, restarting download of {}", model_name);
+            }
+            let file = tokio::fs::File::create(&partial_path)
+                .await
+                .map_err(|e| format!("Failed to create model file: {}", e))?;
+            (0, response.content_length(), file)
+        }
+        reqwest::StatusCode::RANGE_NOT_SATISFIABLE => {
+            // The partial file is bigger than (or equal to) what the server has - start over
+            let _ = tokio::fs::remove_file(&partial_path).await;
+            return Err("Partial download was invalid and has been discarded, please retry".to_string());
+        }
+        status => return Err(format!("Model download failed: HTTP {}", status)),
+    };
+
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        if cancelled.load(Ordering::SeqCst) {
+            drop(file);
+            let _ = tokio::fs::remove_file(&partial_path).await;
+            log::info!("Model download {} cancelled", model_name);
+            let _ = app.emit(
+                "model_download_cancelled",
+                ModelDownloadCancelled {
+                    model_name: model_name.to_string(),
+                },
+            );
+            return Err("Download cancelled".to_string());
+        }
+
+        // On failure the partial file is kept so the next attempt can resume it
+        let bytes = chunk.map_err(|e| format!("Model download interrupted: {}", e))?;
+        file.write_all(&bytes)
+            .await
+            .map_err(|e| format!("Failed to write model file: {}", e))?;
+
+        downloaded += bytes.len() as u64;
+        let _ = app.emit(
+            "model_download_progress",
+            ModelDownloadProgress {
+                model_name: model_name.to_string(),
+                downloaded,
+                total,
+            },
+        );
+    }
+
+    file.flush()
+        .await
+        .map_err(|e| format!("Failed to write model file: {}", e))?;
+    drop(file);
+
+    if let Some(total) = total {
+        if downloaded != total {
+            return Err(format!(
+                "Model download incomplete: got {} of {} bytes (retry to resume)",
+                downloaded, total
+            ));
+        }
+    }
+
+    if let Some(expected) = sha256.map(|h| h.trim().to_lowercase()).filter(|h| !h.is_empty()) {
+        let actual = sha256_file(&partial_path).await?;
+        if actual != expected {
+            let _ = tokio::fs::remove_file(&partial_path).await;
+            return Err(format!(
+                "Model checksum mismatch (expected {}, got {}); the download was discarded",
+                expected, actual
+            ));
+        }
+    }
+
+    tokio::fs::rename(&partial_path, &final_path)
+        .await
+        .map_err(|e| format!("Failed to move downloaded model into place: {}", e))?;
+
+    log::info!("Model {} downloaded ({} bytes)", model_name, downloaded);
+    Ok(final_path.to_string_lossy().to_string())
+}
+
+/// Signal an in-progress download to stop; returns false if nothing was downloading
+#[tauri::command]
+pub async fn cancel_model_download(
+    state: State<'_, ModelDownloadState>,
+    model_name: String,
+) -> Result<bool, String> {
+    match state.downloads.lock().unwrap().get(&model_name) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::SeqCst);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Cancel every running and queued model download; returns how many were signalled
+#[tauri::command]
+pub async fn cancel_all_model_downloads(state: State<'_, ModelDownloadState>) -> Result<usize, String> {
+    let downloads = state.downloads.lock().unwrap();
+    for cancelled in downloads.values() {
+        cancelled.store(true, Ordering::SeqCst);
+    }
+    log::info!("Cancelling {} model download(s)", downloads.len());
+    Ok(downloads.len())
+}
+
+/// File name of the model shipped inside the app bundle
+const BUNDLED_MODEL_NAME: &str = "ggml-base.en.bin";
+
+#[derive(Serialize)]
+pub struct BundledModelInfo {
+    filename: String,
+    /// Size and SHA-256 recorded when the app was built; None if no model was bundled
+    size: Option<u64>,
+    sha256: Option<String>,
+    /// SHA-256 of the copy in the app data `models/` folder, if there is one
+    app_data_sha256: Option<String>,
+    /// True when an app data copy exists and is not the model this release bundles,
+    /// i.e. the UI can offer to refresh it
+    app_data_differs: bool,
+}
+
+/// Describe the model bundled with this build and compare it against the app data copy
+#[tauri::command]
+pub async fn get_bundled_model_info(app: AppHandle) -> Result<BundledModelInfo, String> {
+    let size = env!("BUNDLED_MODEL_SIZE").parse::<u64>().ok().filter(|&s| s > 0);
+    let sha256 = Some(env!("BUNDLED_MODEL_SHA256").to_string()).filter(|h| !h.is_empty());
+
+    let app_data_path = crate::paths::models_dir(&app)?.join(BUNDLED_MODEL_NAME);
+
+    let (app_data_sha256, app_data_differs) = match std::fs::metadata(&app_data_path) {
+        // Different size means different file, no need to hash it
+        Ok(metadata) if size.is_some() && Some(metadata.len()) != size => (None, true),
+        Ok(_) => {
+            let hash = sha256_file(&app_data_path).await?;
+            let differs = sha256.as_ref().is_some_and(|bundled| *bundled != hash);
+            (Some(hash), differs)
+        }
+        Err(_) => (None, false),
+    };
+
+    Ok(BundledModelInfo {
+        filename: BUNDLED_MODEL_NAME.to_string(),
+        size,
+        sha256,
+        app_data_sha256,
+        app_data_differs,
+    })
+}
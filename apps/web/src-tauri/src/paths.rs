@@ -0,0 +1,103 @@
+// Locations of the app's on-disk data, so every module resolves them the same way
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Log why a directory couldn't be resolved and describe it in terms the user can act on.
+/// Sandboxes (Flatpak, Snap, restricted Windows accounts) are the usual cause, so the
+/// message names the folder the app expected to use.
+fn unresolved_dir_error(kind: &str, error: tauri::Error, expected: Option<PathBuf>) -> String {
+    log::error!("Failed to resolve the {} directory: {}", kind, error);
+    match expected {
+        Some(path) => format!(
+            "Could not locate the app's {} folder. If the app runs in a sandbox, allow it to access {}",
+            kind,
+            path.display()
+        ),
+        None => format!(
+            "Could not locate the app's {} folder. If the app runs in a sandbox, allow it to access its data folders",
+            kind
+        ),
+    }
+}
+
+/// Directory for app data (settings, models, recordings)
+pub(crate) fn app_data_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path().app_data_dir().map_err(|e| {
+        // Where Tauri puts it on a normal install
+        let expected = dirs::data_dir().map(|dir| dir.join(&app.config().identifier));
+        unresolved_dir_error("data", e, expected)
+    })
+}
+
+/// Directory bundled resources (models, config files) are installed in
+pub(crate) fn resource_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path().resource_dir().map_err(|e| {
+        // Resources sit next to the executable on Windows and in most Linux bundles
+        let expected = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf));
+        unresolved_dir_error("resource", e, expected)
+    })
+}
+
+/// Directory downloaded Whisper models are stored in
+pub(crate) fn models_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(app_data_dir(app)?.join("models"))
+}
+
+/// Directory saved recordings and converted audio are written to
+pub(crate) fn audio_cache_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(app_data_dir(app)?.join("audio_cache"))
+}
+
+/// `audio_cache_dir`, created first if it doesn't exist yet
+pub(crate) fn ensure_audio_cache_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = audio_cache_dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        log::error!("Failed to create audio directory {:?}: {}", dir, e);
+        format!("Could not create the audio folder {}: {}", dir.display(), e)
+    })?;
+    Ok(dir)
+}
+
+#[derive(Serialize, Debug)]
+pub struct AppPath {
+    pub path: String,
+    pub exists: bool,
+}
+
+impl AppPath {
+    fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            exists: path.exists(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct AppPaths {
+    pub app_data_dir: AppPath,
+    pub models_dir: AppPath,
+    pub audio_cache_dir: AppPath,
+    pub log_dir: AppPath,
+}
+
+/// Where the app keeps its data, models, recordings and logs, for support and debugging
+#[tauri::command]
+pub fn get_app_paths<R: Runtime>(app: AppHandle<R>) -> Result<AppPaths, String> {
+    let log_dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to get app log dir: {}", e))?;
+
+    Ok(AppPaths {
+        app_data_dir: AppPath::new(&app_data_dir(&app)?),
+        models_dir: AppPath::new(&models_dir(&app)?),
+        audio_cache_dir: AppPath::new(&audio_cache_dir(&app)?),
+        log_dir: AppPath::new(&log_dir),
+    })
+}
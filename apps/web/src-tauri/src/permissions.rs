@@ -0,0 +1,208 @@
+// Audio permission checks, so the UI can send the user to the OS settings before a capture
+// thread fails with a vague device error.
+
+use cpal::traits::HostTrait;
+use serde::Serialize;
+
+/// How long the microphone probe listens for a non-silent sample
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const MICROPHONE_PROBE_MS: u64 = 500;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    /// No matching audio device is connected
+    Unavailable,
+    /// Capture isn't supported on this platform
+    Unsupported,
+    /// The OS didn't tell us either way
+    Unknown,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AudioPermissions {
+    pub microphone: PermissionState,
+    pub system_audio: PermissionState,
+}
+
+/// Windows keeps the privacy toggles in the capability consent store: one switch for the
+/// whole device (HKLM), one per user and one for desktop (non-packaged) apps
+#[cfg(target_os = "windows")]
+fn microphone_permission() -> PermissionState {
+    use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+    use winreg::RegKey;
+
+    const CONSENT_KEY: &str =
+        r"Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone";
+
+    if cpal::default_host().default_input_device().is_none() {
+        return PermissionState::Unavailable;
+    }
+
+    let read = |root, path: &str| -> Option<String> {
+        RegKey::predef(root)
+            .open_subkey(path)
+            .and_then(|key| key.get_value::<String, _>("Value"))
+            .ok()
+    };
+    let non_packaged = format!(r"{}\NonPackaged", CONSENT_KEY);
+    let values = [
+        read(HKEY_LOCAL_MACHINE, CONSENT_KEY),
+        read(HKEY_CURRENT_USER, CONSENT_KEY),
+        read(HKEY_CURRENT_USER, &non_packaged),
+    ];
+
+    if values.iter().flatten().any(|v| v.eq_ignore_ascii_case("Deny")) {
+        PermissionState::Denied
+    } else if values.iter().flatten().any(|v| v.eq_ignore_ascii_case("Allow")) {
+        PermissionState::Granted
+    } else {
+        PermissionState::Unknown
+    }
+}
+
+/// macOS tracks microphone consent per app, and AVFoundation reports it without prompting
+#[cfg(target_os = "macos")]
+fn microphone_permission() -> PermissionState {
+    use objc2_av_foundation::{AVAuthorizationStatus, AVCaptureDevice, AVMediaTypeAudio};
+
+    if cpal::default_host().default_input_device().is_none() {
+        return PermissionState::Unavailable;
+    }
+    let Some(media_type) = (unsafe { AVMediaTypeAudio }) else {
+        return PermissionState::Unknown;
+    };
+
+    match unsafe { AVCaptureDevice::authorizationStatusForMediaType(media_type) } {
+        AVAuthorizationStatus::Authorized => PermissionState::Granted,
+        AVAuthorizationStatus::Denied | AVAuthorizationStatus::Restricted => PermissionState::Denied,
+        // NotDetermined: the user hasn't been asked yet
+        _ => PermissionState::Unknown,
+    }
+}
+
+/// Show the macOS microphone prompt and wait for the answer
+/// The OS only prompts while the status is undetermined; afterwards this returns at once.
+#[cfg(target_os = "macos")]
+async fn request_microphone_access() -> Result<(), String> {
+    use block2::RcBlock;
+    use objc2::runtime::Bool;
+    use objc2_av_foundation::{AVCaptureDevice, AVMediaTypeAudio};
+
+    let Some(media_type) = (unsafe { AVMediaTypeAudio }) else {
+        return Ok(());
+    };
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let tx = std::sync::Mutex::new(Some(tx));
+    let handler = RcBlock::new(move |granted: Bool| {
+        if let Some(tx) = tx.lock().unwrap().take() {
+            let _ = tx.send(granted.as_bool());
+        }
+    });
+    unsafe { AVCaptureDevice::requestAccessForMediaType_completionHandler(media_type, &handler) };
+
+    let granted = rx
+        .await
+        .map_err(|e| format!("Microphone permission request was dropped: {}", e))?;
+    log::info!("Microphone access {}", if granted { "granted" } else { "denied" });
+    Ok(())
+}
+
+/// There is no portable query, so briefly open the default microphone and look for any
+/// signal. Silence doesn't prove anything (muted or virtual devices deliver exact zeros
+/// too), so it is reported as Unknown rather than Denied.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn microphone_permission() -> PermissionState {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    let Some(device) = cpal::default_host().default_input_device() else {
+        return PermissionState::Unavailable;
+    };
+
+    let buffer = Arc::new(Mutex::new(Vec::<f32>::new()));
+    let stream = match crate::realtime_transcription::open_microphone_stream(&device, buffer.clone()) {
+        Ok((stream, _)) => stream,
+        Err(e) => {
+            log::warn!("Microphone permission probe could not open the device: {}", e);
+            return PermissionState::Denied;
+        }
+    };
+    std::thread::sleep(Duration::from_millis(MICROPHONE_PROBE_MS));
+    drop(stream);
+
+    if buffer.lock().unwrap().iter().any(|&s| s != 0.0) {
+        PermissionState::Granted
+    } else {
+        PermissionState::Unknown
+    }
+}
+
+/// WASAPI loopback needs no consent, only an output device to capture
+fn system_audio_permission() -> PermissionState {
+    if !cfg!(target_os = "windows") {
+        return PermissionState::Unsupported;
+    }
+    if cpal::default_host().default_output_device().is_none() {
+        return PermissionState::Unavailable;
+    }
+    PermissionState::Granted
+}
+
+async fn current_permissions() -> Result<AudioPermissions, String> {
+    // The Linux probe sleeps and the cpal stream isn't Send, so keep it on a blocking thread
+    tokio::task::spawn_blocking(|| AudioPermissions {
+        microphone: microphone_permission(),
+        system_audio: system_audio_permission(),
+    })
+    .await
+    .map_err(|e| format!("Failed to check audio permissions: {}", e))
+}
+
+/// Report whether the app may capture the microphone and system audio
+/// Never prompts: an undecided macOS microphone is reported as Unknown.
+#[tauri::command]
+pub async fn check_audio_permissions() -> Result<AudioPermissions, String> {
+    let permissions = current_permissions().await?;
+    log::info!("Audio permissions: {:?}", permissions);
+    Ok(permissions)
+}
+
+/// Ask the OS for microphone access and return the resulting permissions
+/// macOS shows its prompt while the user hasn't decided yet. Windows has no prompt for desktop
+/// apps, so there (and on macOS once access was already refused) the microphone privacy
+/// settings page is opened instead.
+#[tauri::command]
+pub async fn request_audio_permissions() -> Result<AudioPermissions, String> {
+    let permissions = current_permissions().await?;
+
+    #[cfg(target_os = "macos")]
+    let permissions = match permissions.microphone {
+        PermissionState::Unknown => {
+            request_microphone_access().await?;
+            current_permissions().await?
+        }
+        // The prompt is only shown once; after a refusal the user has to flip the switch
+        PermissionState::Denied => {
+            tauri_plugin_opener::open_url(
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone",
+                None::<&str>,
+            )
+            .map_err(|e| format!("Failed to open microphone privacy settings: {}", e))?;
+            permissions
+        }
+        _ => permissions,
+    };
+
+    #[cfg(target_os = "windows")]
+    if permissions.microphone == PermissionState::Denied {
+        tauri_plugin_opener::open_url("ms-settings:privacy-microphone", None::<&str>)
+            .map_err(|e| format!("Failed to open microphone privacy settings: {}", e))?;
+    }
+
+    log::info!("Audio permissions after request: {:?}", permissions);
+    Ok(permissions)
+}
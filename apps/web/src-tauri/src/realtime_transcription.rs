@@ -1,97 +1,188 @@
 use std::{
+    collections::VecDeque,
     sync::{Arc, Mutex},
     thread,
-    time::Duration,
-    path::PathBuf,
+    time::{Duration, Instant},
 };
 
-use tauri::{AppHandle, Emitter, Manager, State};
+use chrono::{DateTime, Utc};
+use tauri::{AppHandle, Emitter, State};
 use whisper_rs::{WhisperContext, WhisperContextParameters, FullParams, SamplingStrategy};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 
-/// Find the project root directory by looking for common markers
-fn find_project_root() -> Option<PathBuf> {
-    // Try multiple starting points
-    let starting_points = vec![
-        std::env::current_dir().ok(),
-        std::env::current_exe()
-            .ok()
-            .and_then(|p| p.parent().map(|p| p.to_path_buf())),
-    ];
-    
-    for start in starting_points.into_iter().flatten() {
-        let mut current = start;
-        
-        // Traverse up to find project root (limit to 10 levels to avoid infinite loops)
-        for _ in 0..10 {
-            // Check for common project root markers
-            let has_package_json = current.join("package.json").exists();
-            let has_models = current.join("models").exists();
-            let has_turbo_json = current.join("turbo.json").exists();
-            
-            // If we find the root package.json (not in apps/web) and models folder, we're at project root
-            if (has_package_json || has_turbo_json) && has_models {
-                return Some(current);
-            }
-            
-            // Also check if models folder exists with the model file (this is a strong indicator)
-            if has_models && current.join("models").join("ggml-base.en.bin").exists() {
-                return Some(current);
-            }
-            
-            // Go up one directory
-            match current.parent() {
-                Some(parent) => current = parent.to_path_buf(),
-                None => break,
-            }
-        }
+use crate::audio_utils::{denoise_audio, normalize_peak, peak_amplitude, SILENCE_PEAK_THRESHOLD};
+use crate::system_audio_transcription::{text_after_overlap, MAX_OVERLAP_WORDS};
+use crate::transcription::{
+    ends_sentence, normalize_transcript_text, session_event_name, take_complete_sentences,
+    DecodeTuning,
+};
+
+#[derive(Default)]
+pub struct RealtimeState {
+    running: Arc<Mutex<bool>>,
+    /// When the current session was started; taken by `stop_transcription`
+    started_at: Mutex<Option<DateTime<Utc>>>,
+    /// Latest finalized segments, so a reloaded UI can catch up on what it missed
+    recent: Arc<Mutex<VecDeque<RecentSegment>>>,
+    /// Capture thread of the current or last session, joined on app exit
+    worker: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+impl RealtimeState {
+    /// Tell the capture thread to stop and hand back its handle for joining
+    pub(crate) fn stop_worker(&self) -> Option<thread::JoinHandle<()>> {
+        *self.running.lock().unwrap() = false;
+        self.worker.lock().unwrap().take()
+    }
+}
+
+/// Most segments kept for `get_recent_transcription`; older ones are dropped
+const RECENT_SEGMENT_CAPACITY: usize = 200;
+
+/// A finalized segment as it was emitted, with when it was emitted
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentSegment {
+    pub text: String,
+    pub avg_logprob: Option<f32>,
+    pub emitted_at: DateTime<Utc>,
+}
+
+fn remember_segment(recent: &Mutex<VecDeque<RecentSegment>>, update: &TranscriptionUpdate) {
+    let mut recent = recent.lock().unwrap();
+    if recent.len() == RECENT_SEGMENT_CAPACITY {
+        recent.pop_front();
+    }
+    recent.push_back(RecentSegment {
+        text: update.text.clone(),
+        avg_logprob: update.avg_logprob,
+        emitted_at: Utc::now(),
+    });
+}
+
+/// Time span of a finished microphone transcription session
+#[derive(Debug, Serialize)]
+pub struct TranscriptionSession {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub duration_secs: f64,
+}
+
+/// Optional tuning for the microphone transcription loop (all fields default to current behavior)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RealtimeConfig {
+    /// When set, segments produced within this window are batched into a single
+    /// `transcription_update_batch` event (`Vec<String>`) instead of one `transcription_update` each
+    pub coalesce_window_ms: Option<u64>,
+    /// Feed the previous chunk's text to Whisper as a prompt for better accuracy
+    /// (output repeating the previous chunk is still dropped); default false
+    pub use_context: Option<bool>,
+    /// Anti-hallucination thresholds; unset keeps whisper's defaults
+    pub decode_tuning: Option<DecodeTuning>,
+    /// Emit one update per complete sentence, holding back unfinished text until it ends;
+    /// default false (one update per Whisper segment)
+    pub split_sentences: Option<bool>,
+    /// Run spectral-subtraction noise reduction on each chunk before transcribing;
+    /// helps in noisy rooms at some CPU cost; default false
+    pub denoise: Option<bool>,
+    /// Seconds of audio that must be buffered before the first transcription (default 2)
+    pub min_audio_secs: Option<f32>,
+    /// Seconds of audio transcribed on each pass (default 5)
+    pub chunk_secs: Option<f32>,
+    /// Seconds between passes; each pass still transcribes the last `chunk_secs`, so text
+    /// shows up sooner while words heard again are dropped. Shorter means more CPU; must not
+    /// exceed chunk_secs; default 2 (or chunk_secs, if that is shorter)
+    pub advance_secs: Option<f32>,
+    /// Emit `{ text, avg_logprob }` objects instead of bare strings so the UI can dim
+    /// low-confidence text; default false
+    pub include_confidence: Option<bool>,
+    /// Clean up spacing and capitalize sentence starts before emitting; default false
+    pub normalize_text: Option<bool>,
+    /// Peak level each chunk is normalized to; lower it if quiet recordings come out with
+    /// an amplified noise floor; must be in (0, 1]; default 0.8
+    pub normalize_peak: Option<f32>,
+    /// Stop the session and emit `transcription_auto_stopped` after this many seconds of
+    /// continuous silence, so a forgotten session releases the microphone; default disabled
+    pub auto_stop_after_silence_secs: Option<u64>,
+}
+
+/// Update payload used when `include_confidence` is set. With `split_sentences`, the
+/// confidence is that of the Whisper segment which completed the sentence.
+#[derive(Serialize, Clone, Debug)]
+struct TranscriptionUpdate {
+    text: String,
+    /// Mean token log-probability (closer to 0 = more confident)
+    avg_logprob: Option<f32>,
+}
+
+/// Emit one update, as a bare string unless structured payloads were requested
+fn emit_update(window: &tauri::Window, event: &str, update: TranscriptionUpdate, structured: bool) {
+    if structured {
+        let _ = window.emit(event, update);
+    } else {
+        let _ = window.emit(event, update.text);
     }
-    
-    None
 }
 
-/// Resolve model path, checking bundled resources first (production), then project root (development)
-fn resolve_model_path(app: &AppHandle, model_name: &str) -> Result<PathBuf, String> {
-    // FIRST: Try bundled resources (for production builds - users won't need to download)
-    let resource_path = app.path().resource_dir()
-        .map_err(|e| format!("Failed to get resource dir: {}", e))?
-        .join("models")
-        .join(model_name);
-    
-    if resource_path.exists() {
-        return Ok(resource_path);
+/// Emit a coalesced batch in the same shape as `emit_update`
+fn emit_batch(window: &tauri::Window, event: &str, updates: Vec<TranscriptionUpdate>, structured: bool) {
+    if structured {
+        let _ = window.emit(event, updates);
+    } else {
+        let _ = window.emit(event, updates.into_iter().map(|u| u.text).collect::<Vec<_>>());
     }
-    
-    // SECOND: Try project root models folder (for development)
-    if let Some(project_root) = find_project_root() {
-        let project_model_path = project_root.join("models").join(model_name);
-        if project_model_path.exists() {
-            return Ok(project_model_path);
+}
+
+const DEFAULT_MIN_AUDIO_SECS: f32 = 2.0;
+const DEFAULT_CHUNK_SECS: f32 = 5.0;
+const DEFAULT_ADVANCE_SECS: f32 = 2.0;
+/// Upper bound on speaking rate, used to size how many repeated words a pass may start with
+const MAX_WORDS_PER_SEC: f32 = 4.0;
+
+impl RealtimeConfig {
+    /// Resolve `(min_audio_secs, chunk_secs, advance_secs)`, rejecting values that can't work together
+    fn chunk_timing(&self) -> Result<(f32, f32, f32), String> {
+        let min_audio_secs = self.min_audio_secs.unwrap_or(DEFAULT_MIN_AUDIO_SECS);
+        let chunk_secs = self.chunk_secs.unwrap_or(DEFAULT_CHUNK_SECS);
+        let advance_secs = self.advance_secs.unwrap_or(DEFAULT_ADVANCE_SECS.min(chunk_secs));
+        if min_audio_secs <= 0.0 || !min_audio_secs.is_finite() {
+            return Err(format!("min_audio_secs must be greater than zero (got {})", min_audio_secs));
+        }
+        if chunk_secs <= 0.0 || !chunk_secs.is_finite() {
+            return Err(format!("chunk_secs must be greater than zero (got {})", chunk_secs));
         }
+        if min_audio_secs > chunk_secs {
+            return Err(format!(
+                "min_audio_secs ({}) must not exceed chunk_secs ({})",
+                min_audio_secs, chunk_secs
+            ));
+        }
+        if advance_secs <= 0.0 || !advance_secs.is_finite() {
+            return Err(format!("advance_secs must be greater than zero (got {})", advance_secs));
+        }
+        if advance_secs > chunk_secs {
+            return Err(format!(
+                "advance_secs ({}) must not exceed chunk_secs ({})",
+                advance_secs, chunk_secs
+            ));
+        }
+        Ok((min_audio_secs, chunk_secs, advance_secs))
     }
-    
-    // THIRD: Fallback to app data directory (for user-installed models)
-    let app_data_path = app.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?
-        .join("models")
-        .join(model_name);
-    
-    if app_data_path.exists() {
-        return Ok(app_data_path);
+
+    /// Resolve the inactivity auto-stop, if enabled
+    fn auto_stop_after_silence(&self) -> Result<Option<Duration>, String> {
+        match self.auto_stop_after_silence_secs {
+            Some(0) => Err("auto_stop_after_silence_secs must be greater than zero".to_string()),
+            secs => Ok(secs.map(Duration::from_secs)),
+        }
     }
-    
-    // If none exist, return error with all checked paths
-    Err(format!(
-        "Model file not found. Searched in:\n1. Bundled resources\n2. Project root models folder\n3. {:?}\n\nFor development: Place the model in the project root: models/{}\nFor production: The model should be bundled with the app.",
-        app_data_path,
-        model_name
-    ))
 }
 
-#[derive(Default)]
-pub struct RealtimeState {
-    running: Arc<Mutex<bool>>,
+#[derive(Serialize, Clone)]
+struct TranscriptionAutoStopped {
+    silence_secs: u64,
 }
 
 #[tauri::command]
@@ -99,38 +190,226 @@ pub async fn start_transcription(
     app: AppHandle,
     window: tauri::Window,
     state: State<'_, RealtimeState>,
-) -> Result<(), String> {
+    config: Option<RealtimeConfig>,
+    session_id: Option<String>,
+) -> Result<String, String> {
+    let config = config.unwrap_or_default();
+    config.chunk_timing()?;
+    normalize_peak(config.normalize_peak)?;
+    config.auto_stop_after_silence()?;
     let mut running = state.running.lock().unwrap();
     if *running {
         return Err("Transcription already running".into());
     }
     *running = true;
+    *state.started_at.lock().unwrap() = Some(Utc::now());
+    state.recent.lock().unwrap().clear();
 
     // Resolve model path (check project root first)
     let model_name = "ggml-base.en.bin";
-    let model_path = resolve_model_path(&app, model_name)?;
+    let model_path = crate::transcription::resolve_model_path(&app, model_name)?;
 
     let model_path_str = model_path.to_str()
         .ok_or("Invalid model path")?
         .to_string();
 
     let window_clone = window.clone();
+    let window_error = window.clone();
     let running_clone = state.running.clone();
-
-    thread::spawn(move || {
-        if let Err(err) = capture_and_transcribe(window_clone, running_clone, model_path_str) {
-            eprintln!("Error during transcription: {:?}", err);
+    let running_error = state.running.clone();
+    let recent = state.recent.clone();
+
+    let update_event = session_event_name("transcription_update", session_id.as_deref());
+    let error_event = session_event_name("transcription_error", session_id.as_deref());
+
+    log::info!("Starting microphone transcription with model {}", model_path_str);
+
+    let worker = thread::spawn(move || {
+        if let Err(err) = capture_and_transcribe(
+            window_clone,
+            running_clone,
+            model_path_str,
+            config,
+            session_id,
+            recent,
+        ) {
+            log::error!("Microphone transcription failed: {:?}", err);
+            // Reset state so the UI can start again after e.g. a denied or vanished microphone
+            *running_error.lock().unwrap() = false;
+            let _ = window_error.emit(&error_event, err.to_string());
         }
     });
+    *state.worker.lock().unwrap() = Some(worker);
 
-    Ok(())
+    Ok(update_event)
 }
 
+/// Stop the microphone transcription and report when the session ran
+/// Returns None if no session was started since the last stop.
 #[tauri::command]
-pub async fn stop_transcription(state: State<'_, RealtimeState>) -> Result<(), String> {
+pub async fn stop_transcription(
+    state: State<'_, RealtimeState>,
+) -> Result<Option<TranscriptionSession>, String> {
     let mut running = state.running.lock().unwrap();
     *running = false;
-    Ok(())
+    drop(running);
+
+    let ended_at = Utc::now();
+    let session = state
+        .started_at
+        .lock()
+        .unwrap()
+        .take()
+        .map(|started_at| TranscriptionSession {
+            started_at,
+            ended_at,
+            duration_secs: (ended_at - started_at).num_milliseconds() as f64 / 1000.0,
+        });
+
+    Ok(session)
+}
+
+/// The last `n` (default all retained, at most 200) segments of the current or most recent
+/// microphone session, oldest first, so a reloaded UI can re-sync
+#[tauri::command]
+pub async fn get_recent_transcription(
+    state: State<'_, RealtimeState>,
+    n: Option<usize>,
+) -> Result<Vec<RecentSegment>, String> {
+    let recent = state.recent.lock().unwrap();
+    let n = n.unwrap_or(recent.len()).min(recent.len());
+    Ok(recent.iter().skip(recent.len() - n).cloned().collect())
+}
+
+/// Sample rates tried after 16kHz and the device default, most common first
+const FALLBACK_SAMPLE_RATES: [u32; 3] = [48000, 44100, 32000];
+
+/// Input configs to try, in order: 16kHz (what Whisper wants), the device default,
+/// then other common rates. f32 configs are preferred within each rate.
+fn candidate_input_configs(device: &cpal::Device) -> Vec<cpal::SupportedStreamConfig> {
+    let mut supported: Vec<cpal::SupportedStreamConfigRange> = device
+        .supported_input_configs()
+        .map(|configs| configs.collect())
+        .unwrap_or_default();
+    supported.sort_by_key(|range| range.sample_format() != cpal::SampleFormat::F32);
+
+    let at_rate = |rate: u32| -> Vec<cpal::SupportedStreamConfig> {
+        supported
+            .iter()
+            .filter(|range| range.min_sample_rate().0 <= rate && range.max_sample_rate().0 >= rate)
+            .map(|range| range.clone().with_sample_rate(cpal::SampleRate(rate)))
+            .collect()
+    };
+
+    let mut candidates = at_rate(16000);
+    match device.default_input_config() {
+        Ok(default) => candidates.push(default),
+        Err(e) => log::warn!("Failed to get default microphone config: {}", e),
+    }
+    for rate in FALLBACK_SAMPLE_RATES {
+        candidates.extend(at_rate(rate));
+    }
+
+    let mut unique = Vec::with_capacity(candidates.len());
+    for candidate in candidates {
+        if !unique.contains(&candidate) {
+            unique.push(candidate);
+        }
+    }
+    unique
+}
+
+/// Open an input stream for `config`, converting integer sample formats to f32
+fn build_capture_stream(
+    device: &cpal::Device,
+    config: &cpal::SupportedStreamConfig,
+    buffer: Arc<Mutex<Vec<f32>>>,
+) -> Result<cpal::Stream, cpal::BuildStreamError> {
+    let stream_config: cpal::StreamConfig = config.config();
+    let on_error = |err: cpal::StreamError| {
+        log::error!("Microphone audio stream error: {}", err);
+    };
+
+    match config.sample_format() {
+        cpal::SampleFormat::F32 => device.build_input_stream(
+            &stream_config,
+            move |data: &[f32], _| {
+                buffer.lock().unwrap().extend_from_slice(data);
+            },
+            on_error,
+            None,
+        ),
+        cpal::SampleFormat::I16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[i16], _| {
+                buffer
+                    .lock()
+                    .unwrap()
+                    .extend(data.iter().map(|&s| s as f32 / i16::MAX as f32));
+            },
+            on_error,
+            None,
+        ),
+        cpal::SampleFormat::U16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[u16], _| {
+                buffer
+                    .lock()
+                    .unwrap()
+                    .extend(data.iter().map(|&s| (s as f32 - 32768.0) / 32768.0));
+            },
+            on_error,
+            None,
+        ),
+        _ => Err(cpal::BuildStreamError::StreamConfigNotSupported),
+    }
+}
+
+/// Start an input stream on the first format the device accepts, appending interleaved f32
+/// samples to `buffer`; returns the stream (keep it alive) and the format that was opened
+pub(crate) fn open_microphone_stream(
+    device: &cpal::Device,
+    buffer: Arc<Mutex<Vec<f32>>>,
+) -> Result<(cpal::Stream, cpal::SupportedStreamConfig)> {
+    // Try each candidate format until the device accepts one (16kHz first, as Whisper needs it)
+    let candidates = candidate_input_configs(device);
+    let mut attempted = Vec::new();
+    let mut opened = None;
+    for candidate in candidates {
+        let description = format!(
+            "{}Hz/{}ch/{:?}",
+            candidate.sample_rate().0,
+            candidate.channels(),
+            candidate.sample_format()
+        );
+        log::info!("Trying microphone format {}", description);
+
+        let stream = match build_capture_stream(device, &candidate, buffer.clone()) {
+            Ok(stream) => stream,
+            Err(cpal::BuildStreamError::DeviceNotAvailable) => {
+                return Err(anyhow::anyhow!("Microphone unavailable: input device is no longer available"));
+            }
+            Err(e) => {
+                log::warn!("Microphone format {} rejected: {}", description, e);
+                attempted.push(description);
+                continue;
+            }
+        };
+        if let Err(e) = stream.play() {
+            log::warn!("Microphone format {} failed to start: {}", description, e);
+            attempted.push(description);
+            continue;
+        }
+        opened = Some((stream, candidate));
+        break;
+    }
+
+    opened.ok_or_else(|| {
+        anyhow::anyhow!(
+            "Microphone unavailable: the input device doesn't support any usable audio format (tried {})",
+            if attempted.is_empty() { "none".to_string() } else { attempted.join(", ") }
+        )
+    })
 }
 
 /// Capture audio from microphone and feed it to Whisper in short chunks.
@@ -138,6 +417,9 @@ fn capture_and_transcribe(
     window: tauri::Window,
     running: Arc<Mutex<bool>>,
     model_path: String,
+    config: RealtimeConfig,
+    session_id: Option<String>,
+    recent: Arc<Mutex<VecDeque<RecentSegment>>>,
 ) -> Result<()> {
     // Load whisper model
     let ctx_params = WhisperContextParameters::default();
@@ -147,61 +429,88 @@ fn capture_and_transcribe(
     let host = cpal::default_host();
     let device = host
         .default_input_device()
-        .ok_or_else(|| anyhow::anyhow!("No input device found"))?;
-
-    // Try to get a config with 16kHz (Whisper requirement)
-    let mut config = device.default_input_config()?;
-    let target_sample_rate = 16000u32;
-
-    // Try to find a config that supports 16kHz
-    if let Ok(supported_configs) = device.supported_input_configs() {
-        for supported in supported_configs {
-            if supported.min_sample_rate().0 <= target_sample_rate
-                && supported.max_sample_rate().0 >= target_sample_rate {
-                config = supported.with_sample_rate(cpal::SampleRate(target_sample_rate));
-                break;
-            }
-        }
-    }
-
-    let sample_rate = config.sample_rate().0;
-    let channels = config.channels();
-    let needs_resample = sample_rate != 16000;
+        .ok_or_else(|| anyhow::anyhow!("Microphone unavailable: no input device found"))?;
 
     let audio_buffer = Arc::new(Mutex::new(Vec::<f32>::new()));
-    let buffer_clone = audio_buffer.clone();
     let running_clone = running.clone();
 
-    // Build CPAL input stream
-    let stream = device.build_input_stream(
-        &config.into(),
-        move |data: &[f32], _| {
-            let mut buffer = buffer_clone.lock().unwrap();
-            buffer.extend_from_slice(data);
-        },
-        move |err| {
-            eprintln!("Audio stream error: {}", err);
-        },
-        None,
-    )?;
-
-    stream.play()?;
+    let (stream, stream_config) = open_microphone_stream(&device, audio_buffer.clone())?;
+
+    let sample_rate = stream_config.sample_rate().0;
+    let channels = stream_config.channels();
+    let needs_resample = sample_rate != 16000;
+
+    log::info!(
+        "Microphone capture using {}Hz, {} channel(s), {:?} (resampling: {})",
+        sample_rate,
+        channels,
+        stream_config.sample_format(),
+        needs_resample
+    );
+
+    let update_event = session_event_name("transcription_update", session_id.as_deref());
+    let batch_event = session_event_name("transcription_update_batch", session_id.as_deref());
+    let auto_stopped_event = session_event_name("transcription_auto_stopped", session_id.as_deref());
+
+    // Inactivity auto-stop (only used when auto_stop_after_silence_secs is set)
+    let auto_stop_after = config
+        .auto_stop_after_silence()
+        .map_err(anyhow::Error::msg)?;
+    let mut silence_started: Option<Instant> = None;
+
+    // Coalescing state (only used when coalesce_window_ms is set)
+    let coalesce_window = config.coalesce_window_ms.map(Duration::from_millis);
+    let mut pending_updates: Vec<TranscriptionUpdate> = Vec::new();
+    let mut last_flush = Instant::now();
+
+    // Sliding context (only used when use_context is set)
+    let use_context = config.use_context.unwrap_or(false);
+    let mut previous_text = String::new();
+
+    // Sentence splitting (only used when split_sentences is set)
+    let split_sentences = config.split_sentences.unwrap_or(false);
+    let mut sentence_buffer = String::new();
+
+    let denoise = config.denoise.unwrap_or(false);
+    // Validated when the command started
+    let target_peak = normalize_peak(config.normalize_peak).map_err(anyhow::Error::msg)?;
+    let include_confidence = config.include_confidence.unwrap_or(false);
+
+    // Text normalization (only used when normalize_text is set); chunks often continue the
+    // previous sentence, so capitalization follows how the last update ended
+    let normalize_text = config.normalize_text.unwrap_or(false);
+    let mut starts_sentence = true;
+
+    // Validated in start_transcription
+    let (min_audio_secs, chunk_secs, advance_secs) = config
+        .chunk_timing()
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let advance = Duration::from_secs_f32(advance_secs);
+
+    // Last words emitted, compared against each new segment: consecutive windows overlap by
+    // chunk_secs - advance_secs, so every pass starts by hearing speech that was already emitted
+    let mut emitted_tail = String::new();
+    let overlap_words = MAX_OVERLAP_WORDS
+        .max(((chunk_secs - advance_secs) * MAX_WORDS_PER_SEC).ceil() as usize);
 
     // Run transcription loop
+    let mut last_pass = Instant::now();
     while *running_clone.lock().unwrap() {
-        std::thread::sleep(Duration::from_secs(5)); // every 5s process chunk
+        // Start a pass every advance_secs; one that ran longer is followed immediately
+        std::thread::sleep(advance.saturating_sub(last_pass.elapsed()));
+        last_pass = Instant::now();
 
         let mut buffer = audio_buffer.lock().unwrap();
 
-        // Need at least 2 seconds of audio for better transcription
-        let min_samples = (sample_rate * channels as u32 * 2) as usize;
+        // Need at least min_audio_secs of audio for better transcription
+        let min_samples = (sample_rate as f32 * channels as f32 * min_audio_secs) as usize;
         if buffer.len() < min_samples {
             drop(buffer);
             continue; // not enough audio yet
         }
 
-        // Take last 5 seconds of audio (longer chunks work better with Whisper)
-        let chunk_samples = (sample_rate * channels as u32 * 5) as usize;
+        // Take the last chunk_secs of audio (longer chunks work better with Whisper)
+        let chunk_samples = (sample_rate as f32 * channels as f32 * chunk_secs) as usize;
         let buffer_len = buffer.len();
         let start = buffer_len.saturating_sub(chunk_samples);
         let raw_chunk: Vec<f32> = buffer[start..].to_vec();
@@ -233,8 +542,37 @@ fn capture_and_transcribe(
             continue;
         }
 
+        // Same level check the system audio loop uses to skip silence
+        if peak_amplitude(&resampled_chunk) < SILENCE_PEAK_THRESHOLD {
+            let silent_since = *silence_started.get_or_insert_with(Instant::now);
+            if let Some(auto_stop_after) = auto_stop_after {
+                if silent_since.elapsed() >= auto_stop_after {
+                    log::info!(
+                        "Microphone transcription auto-stopped after {}s of silence",
+                        auto_stop_after.as_secs()
+                    );
+                    *running_clone.lock().unwrap() = false;
+                    let _ = window.emit(
+                        &auto_stopped_event,
+                        TranscriptionAutoStopped {
+                            silence_secs: auto_stop_after.as_secs(),
+                        },
+                    );
+                    break;
+                }
+            }
+        } else {
+            silence_started = None;
+        }
+
+        let resampled_chunk = if denoise {
+            denoise_audio(&resampled_chunk)
+        } else {
+            resampled_chunk
+        };
+
         // Normalize audio level to improve transcription quality
-        let processed_chunk = normalize_audio(&resampled_chunk);
+        let processed_chunk = normalize_audio(&resampled_chunk, target_peak);
 
         // Create a new whisper state for each chunk to avoid state accumulation issues
         let mut whisper_state = ctx.create_state()
@@ -253,11 +591,24 @@ fn capture_and_transcribe(
         params.set_suppress_nst(false); // Don't suppress non-speech tokens - let Whisper decide
         params.set_n_threads(4);
         params.set_max_len(0); // 0 = no limit, let Whisper decide segment length
+        if let Some(ref tuning) = config.decode_tuning {
+            tuning.apply(&mut params);
+        }
+        let prompt = crate::transcription::context_prompt(&previous_text).to_string();
+        if use_context && !prompt.is_empty() {
+            params.set_initial_prompt(&prompt);
+        }
 
         if let Ok(_) = whisper_state.full(params, &processed_chunk) {
             if let Ok(num_segments) = whisper_state.full_n_segments() {
+                let mut chunk_text = String::new();
                 for i in 0..num_segments {
                     if let Ok(text) = whisper_state.full_get_segment_text(i) {
+                        let avg_logprob = if include_confidence {
+                            crate::transcription::segment_avg_logprob(&ctx, &whisper_state, i)
+                        } else {
+                            None
+                        };
                         let text = text.trim();
                         // Filter out empty text, timestamp-only segments, and very short segments
                         // Whisper sometimes produces segments with just punctuation or timestamps
@@ -265,20 +616,106 @@ fn capture_and_transcribe(
                             && text.len() > 1
                             && !text.starts_with("[_TT_")
                             && !text.starts_with("[_") {
-                            let _ = window.emit("transcription_update", text);
+                            // With context on, Whisper may echo the prompt - drop repeats
+                            if use_context
+                                && previous_text.to_lowercase().ends_with(&text.to_lowercase())
+                            {
+                                continue;
+                            }
+                            let deduped = text_after_overlap(&emitted_tail, text, overlap_words);
+                            if deduped.is_empty() {
+                                continue;
+                            }
+                            let text = deduped.as_str();
+                            emitted_tail.push(' ');
+                            emitted_tail.push_str(text);
+                            let tail_words: Vec<&str> = emitted_tail.split_whitespace().collect();
+                            emitted_tail = tail_words[tail_words.len().saturating_sub(overlap_words)..].join(" ");
+                            if use_context {
+                                chunk_text.push_str(text);
+                                chunk_text.push(' ');
+                            }
+                            let outputs = if split_sentences {
+                                if !sentence_buffer.is_empty() {
+                                    sentence_buffer.push(' ');
+                                }
+                                sentence_buffer.push_str(text);
+                                take_complete_sentences(&mut sentence_buffer)
+                            } else {
+                                vec![text.to_string()]
+                            };
+                            for output in outputs {
+                                let output = if normalize_text {
+                                    let normalized =
+                                        normalize_transcript_text(&output, Some("en"), starts_sentence);
+                                    starts_sentence = ends_sentence(&normalized);
+                                    normalized
+                                } else {
+                                    output
+                                };
+                                crate::captions::publish_caption(&window, "microphone", &output);
+                                let update = TranscriptionUpdate {
+                                    text: output,
+                                    avg_logprob,
+                                };
+                                remember_segment(&recent, &update);
+                                if coalesce_window.is_some() {
+                                    pending_updates.push(update);
+                                } else {
+                                    emit_update(&window, &update_event, update, include_confidence);
+                                }
+                            }
                         }
                     }
                 }
+                if use_context && !chunk_text.trim().is_empty() {
+                    previous_text = chunk_text.trim().to_string();
+                }
             }
         }
+
+        if let Some(coalesce_window) = coalesce_window {
+            if !pending_updates.is_empty() && last_flush.elapsed() >= coalesce_window {
+                let batch: Vec<TranscriptionUpdate> = pending_updates.drain(..).collect();
+                emit_batch(&window, &batch_event, batch, include_confidence);
+                last_flush = Instant::now();
+            }
+        }
+    }
+
+    // The last sentence may never get its punctuation - emit it as-is
+    let remainder = sentence_buffer.trim();
+    if !remainder.is_empty() {
+        let remainder = if normalize_text {
+            normalize_transcript_text(remainder, Some("en"), starts_sentence)
+        } else {
+            remainder.to_string()
+        };
+        crate::captions::publish_caption(&window, "microphone", &remainder);
+        let update = TranscriptionUpdate {
+            text: remainder,
+            avg_logprob: None,
+        };
+        remember_segment(&recent, &update);
+        if coalesce_window.is_some() {
+            pending_updates.push(update);
+        } else {
+            emit_update(&window, &update_event, update, include_confidence);
+        }
+    }
+
+    // Flush anything still waiting in the coalescing window
+    if !pending_updates.is_empty() {
+        emit_batch(&window, &batch_event, pending_updates, include_confidence);
     }
 
     drop(stream);
+    log::info!("Microphone transcription stopped");
     Ok(())
 }
 
 /// Normalize audio to a target peak level
-fn normalize_audio(input: &[f32]) -> Vec<f32> {
+fn normalize_audio(input: &[f32], target_peak: f32) -> Vec<f32> {
     if input.is_empty() {
         return Vec::new();
     }
@@ -293,8 +730,6 @@ fn normalize_audio(input: &[f32]) -> Vec<f32> {
         return input.to_vec();
     }
 
-    // Normalize to 0.8 peak (leave some headroom)
-    let target_peak = 0.8;
     let scale = target_peak / max_val;
 
     input.iter().map(|&x| (x * scale).clamp(-1.0, 1.0)).collect()
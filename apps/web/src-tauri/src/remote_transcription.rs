@@ -0,0 +1,340 @@
+// Cloud speech-to-text for machines where local Whisper is too slow or no model is installed.
+// Results use the same segment shape as local transcription so the UI can switch freely.
+
+use base64::Engine;
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use crate::gemini::{http_client, request_error};
+use crate::transcription::TranscriptionSegment;
+
+const OPENAI_TRANSCRIPTION_URL: &str = "https://api.openai.com/v1/audio/transcriptions";
+const OPENAI_TRANSCRIPTION_MODEL: &str = "whisper-1";
+const DEEPGRAM_LISTEN_URL: &str = "https://api.deepgram.com/v1/listen";
+const DEEPGRAM_MODEL: &str = "nova-2";
+
+/// Containers both providers accept, as (MIME type, file extension for the upload)
+const AUDIO_FORMATS: [(&str, &str); 6] = [
+    ("audio/wav", "wav"),
+    ("audio/mpeg", "mp3"),
+    ("audio/ogg", "ogg"),
+    ("audio/flac", "flac"),
+    ("audio/webm", "webm"),
+    ("audio/mp4", "m4a"),
+];
+
+/// Other names browsers and recorders use for the types in `AUDIO_FORMATS`
+const AUDIO_MIME_ALIASES: [(&str, &str); 8] = [
+    ("audio/x-wav", "audio/wav"),
+    ("audio/wave", "audio/wav"),
+    ("audio/mp3", "audio/mpeg"),
+    ("audio/x-flac", "audio/flac"),
+    ("audio/m4a", "audio/mp4"),
+    ("audio/x-m4a", "audio/mp4"),
+    ("video/webm", "audio/webm"),
+    ("application/ogg", "audio/ogg"),
+];
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteProvider {
+    OpenAi,
+    Deepgram,
+}
+
+#[derive(Deserialize)]
+struct OpenAiTranscription {
+    text: String,
+    #[serde(default)]
+    segments: Vec<OpenAiSegment>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiSegment {
+    text: String,
+    start: f64,
+    end: f64,
+}
+
+#[derive(Deserialize)]
+struct DeepgramResponse {
+    results: DeepgramResults,
+}
+
+#[derive(Deserialize)]
+struct DeepgramResults {
+    #[serde(default)]
+    utterances: Vec<DeepgramUtterance>,
+    #[serde(default)]
+    channels: Vec<DeepgramChannel>,
+}
+
+#[derive(Deserialize)]
+struct DeepgramUtterance {
+    transcript: String,
+    start: f64,
+    end: f64,
+}
+
+#[derive(Deserialize)]
+struct DeepgramChannel {
+    #[serde(default)]
+    alternatives: Vec<DeepgramAlternative>,
+}
+
+#[derive(Deserialize)]
+struct DeepgramAlternative {
+    transcript: String,
+}
+
+/// Recognize the container from its magic bytes
+fn sniff_audio_mime(audio: &[u8]) -> Option<&'static str> {
+    if audio.len() >= 12 && &audio[..4] == b"RIFF" && &audio[8..12] == b"WAVE" {
+        Some("audio/wav")
+    } else if audio.starts_with(b"ID3") || (audio.len() >= 2 && audio[0] == 0xFF && audio[1] & 0xE0 == 0xE0) {
+        Some("audio/mpeg")
+    } else if audio.starts_with(b"OggS") {
+        Some("audio/ogg")
+    } else if audio.starts_with(b"fLaC") {
+        Some("audio/flac")
+    } else if audio.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        Some("audio/webm")
+    } else if audio.len() >= 8 && &audio[4..8] == b"ftyp" {
+        Some("audio/mp4")
+    } else {
+        None
+    }
+}
+
+/// Pick the MIME type and file extension to upload with: the declared type if there is one
+/// (codec parameters are ignored), otherwise whatever the bytes look like
+fn audio_format(declared: Option<&str>, audio: &[u8]) -> Result<(&'static str, &'static str), String> {
+    let mime = match declared.map(|m| m.split(';').next().unwrap_or("").trim().to_ascii_lowercase()) {
+        Some(mime) if !mime.is_empty() => AUDIO_MIME_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == mime)
+            .map(|(_, canonical)| *canonical)
+            .or_else(|| AUDIO_FORMATS.iter().map(|(m, _)| *m).find(|m| *m == mime))
+            .ok_or_else(|| format!("Unsupported audio type: {}", mime))?,
+        _ => sniff_audio_mime(audio).ok_or_else(|| {
+            "Unrecognized audio format; pass mime_type (WAV, MP3, OGG, FLAC, WebM or M4A)".to_string()
+        })?,
+    };
+    AUDIO_FORMATS
+        .iter()
+        .copied()
+        .find(|(m, _)| *m == mime)
+        .ok_or_else(|| format!("Unsupported audio type: {}", mime))
+}
+
+/// Transcribe base64-encoded audio (WAV, MP3, OGG, FLAC, WebM or M4A) with a cloud provider
+/// ("openai" or "deepgram") and return timestamped segments like
+/// `transcribe_audio_with_timestamps`. The type comes from `mime_type`, then the data URL
+/// prefix, then the file's magic bytes.
+#[tauri::command]
+pub async fn transcribe_remote(
+    app: AppHandle,
+    audio_base64: String,
+    provider: RemoteProvider,
+    api_key: String,
+    language: Option<String>,
+    mime_type: Option<String>,
+) -> Result<Vec<TranscriptionSegment>, String> {
+    if api_key.trim().is_empty() {
+        return Err("API key is required for remote transcription".to_string());
+    }
+
+    // Accept both raw base64 and data URLs from the frontend
+    let (data_url_mime, encoded) = match audio_base64.split_once(";base64,") {
+        Some((header, data)) => (header.strip_prefix("data:"), data),
+        None => (None, audio_base64.as_str()),
+    };
+    let audio = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("Failed to decode audio: {}", e))?;
+    if audio.is_empty() {
+        return Err("Audio data is empty".to_string());
+    }
+    let format = audio_format(mime_type.as_deref().or(data_url_mime), &audio)?;
+
+    let segments = match provider {
+        RemoteProvider::OpenAi => transcribe_openai(&app, audio, format, &api_key, language).await?,
+        RemoteProvider::Deepgram => {
+            transcribe_deepgram(&app, audio, format.0, &api_key, language).await?
+        }
+    };
+
+    log::info!(
+        "Remote transcription ({:?}) returned {} segments",
+        provider,
+        segments.len()
+    );
+    Ok(segments)
+}
+
+async fn transcribe_openai(
+    app: &AppHandle,
+    audio: Vec<u8>,
+    (mime, extension): (&str, &str),
+    api_key: &str,
+    language: Option<String>,
+) -> Result<Vec<TranscriptionSegment>, String> {
+    // OpenAI goes by the file name's extension
+    let file = Part::bytes(audio)
+        .file_name(format!("audio.{}", extension))
+        .mime_str(mime)
+        .map_err(|e| format!("Failed to build upload: {}", e))?;
+    let mut form = Form::new()
+        .part("file", file)
+        .text("model", OPENAI_TRANSCRIPTION_MODEL)
+        .text("response_format", "verbose_json")
+        .text("timestamp_granularities[]", "segment");
+    if let Some(language) = language {
+        form = form.text("language", language);
+    }
+
+    let response = http_client(app)?
+        .post(OPENAI_TRANSCRIPTION_URL)
+        .bearer_auth(api_key)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| request_error(app, e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("OpenAI transcription error ({}): {}", status, body));
+    }
+
+    let result: OpenAiTranscription = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse OpenAI response: {}", e))?;
+
+    if result.segments.is_empty() {
+        return Ok(whole_text_segment(result.text));
+    }
+
+    Ok(result
+        .segments
+        .into_iter()
+        .map(|s| TranscriptionSegment {
+            text: s.text.trim().to_string(),
+            start: s.start,
+            end: s.end,
+        })
+        .filter(|s| !s.text.is_empty())
+        .collect())
+}
+
+async fn transcribe_deepgram(
+    app: &AppHandle,
+    audio: Vec<u8>,
+    mime: &str,
+    api_key: &str,
+    language: Option<String>,
+) -> Result<Vec<TranscriptionSegment>, String> {
+    let mut query = vec![
+        ("model", DEEPGRAM_MODEL.to_string()),
+        ("smart_format", "true".to_string()),
+        ("utterances", "true".to_string()),
+    ];
+    if let Some(language) = language {
+        query.push(("language", language));
+    }
+
+    let response = http_client(app)?
+        .post(DEEPGRAM_LISTEN_URL)
+        .query(&query)
+        .header("Authorization", format!("Token {}", api_key))
+        .header("Content-Type", mime)
+        .body(audio)
+        .send()
+        .await
+        .map_err(|e| request_error(app, e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Deepgram transcription error ({}): {}", status, body));
+    }
+
+    let result: DeepgramResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Deepgram response: {}", e))?;
+
+    if result.results.utterances.is_empty() {
+        let text = result
+            .results
+            .channels
+            .into_iter()
+            .next()
+            .and_then(|c| c.alternatives.into_iter().next())
+            .map(|a| a.transcript)
+            .unwrap_or_default();
+        return Ok(whole_text_segment(text));
+    }
+
+    Ok(result
+        .results
+        .utterances
+        .into_iter()
+        .map(|u| TranscriptionSegment {
+            text: u.transcript.trim().to_string(),
+            start: u.start,
+            end: u.end,
+        })
+        .filter(|s| !s.text.is_empty())
+        .collect())
+}
+
+/// Providers without timing info still return one segment so callers can treat both alike
+fn whole_text_segment(text: String) -> Vec<TranscriptionSegment> {
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Vec::new();
+    }
+    vec![TranscriptionSegment {
+        text,
+        start: 0.0,
+        end: 0.0,
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffs_common_containers() {
+        assert_eq!(sniff_audio_mime(b"RIFF\x24\0\0\0WAVEfmt "), Some("audio/wav"));
+        assert_eq!(sniff_audio_mime(b"ID3\x04\0"), Some("audio/mpeg"));
+        assert_eq!(sniff_audio_mime(&[0xFF, 0xFB, 0x90, 0x00]), Some("audio/mpeg"));
+        assert_eq!(sniff_audio_mime(b"OggS\0\x02"), Some("audio/ogg"));
+        assert_eq!(sniff_audio_mime(b"fLaC\0\0"), Some("audio/flac"));
+        assert_eq!(sniff_audio_mime(&[0x1A, 0x45, 0xDF, 0xA3, 0x9F]), Some("audio/webm"));
+        assert_eq!(sniff_audio_mime(b"\0\0\0\x20ftypM4A "), Some("audio/mp4"));
+        assert_eq!(sniff_audio_mime(b"hello world"), None);
+    }
+
+    #[test]
+    fn declared_type_wins_and_aliases_resolve() {
+        let wav = b"RIFF\x24\0\0\0WAVEfmt ";
+        assert_eq!(audio_format(None, wav), Ok(("audio/wav", "wav")));
+        assert_eq!(
+            audio_format(Some("audio/webm;codecs=opus"), wav),
+            Ok(("audio/webm", "webm"))
+        );
+        assert_eq!(audio_format(Some("Audio/X-M4A"), wav), Ok(("audio/mp4", "m4a")));
+        assert_eq!(audio_format(Some(""), wav), Ok(("audio/wav", "wav")));
+    }
+
+    #[test]
+    fn rejects_unknown_audio() {
+        assert!(audio_format(Some("video/quicktime"), b"RIFF").is_err());
+        assert!(audio_format(None, b"not audio").is_err());
+    }
+}
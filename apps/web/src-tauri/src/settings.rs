@@ -0,0 +1,46 @@
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+/// Store file for settings that the Rust side needs to read (lives in the app data dir)
+pub const SETTINGS_STORE: &str = "settings.json";
+
+/// Read a string setting, returning None if it's missing, empty, or not a string
+pub fn get_string<R: Runtime>(app: &AppHandle<R>, key: &str) -> Option<String> {
+    let store = app.store(SETTINGS_STORE).ok()?;
+    store
+        .get(key)
+        .and_then(|value| value.as_str().map(|s| s.to_string()))
+        .filter(|s| !s.trim().is_empty())
+}
+
+/// Read a boolean setting, returning None if it's missing or not a bool
+pub fn get_bool<R: Runtime>(app: &AppHandle<R>, key: &str) -> Option<bool> {
+    let store = app.store(SETTINGS_STORE).ok()?;
+    store.get(key).and_then(|value| value.as_bool())
+}
+
+/// Write a setting and persist the store to disk immediately
+pub fn set_value<R: Runtime>(
+    app: &AppHandle<R>,
+    key: &str,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.set(key, value);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+/// Remove a setting and persist the store to disk immediately
+pub fn delete_key<R: Runtime>(app: &AppHandle<R>, key: &str) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    store.delete(key);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
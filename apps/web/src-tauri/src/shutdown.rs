@@ -0,0 +1,67 @@
+// Stopping capture threads on app exit, so none of them keeps the audio device open.
+
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+
+use crate::realtime_transcription::RealtimeState;
+use crate::system_audio_transcription::{SystemAudioRecordingState, SystemAudioTranscriptionState};
+
+/// Longest the exit waits for all capture threads together
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+const JOIN_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Wait for `handle` until `deadline`; returns false if the thread was still running
+/// (std has no timed join, so poll `is_finished` and only join once it can't block)
+fn join_until(name: &str, handle: JoinHandle<()>, deadline: Instant) -> bool {
+    while !handle.is_finished() {
+        if Instant::now() >= deadline {
+            log::warn!("{} thread did not stop within {:?}", name, SHUTDOWN_TIMEOUT);
+            return false;
+        }
+        thread::sleep(JOIN_POLL_INTERVAL);
+    }
+    if handle.join().is_err() {
+        log::warn!("{} thread panicked while stopping", name);
+    }
+    true
+}
+
+/// Stop every microphone / system audio capture thread and wait for them to release the
+/// devices. All flags are cleared before waiting so the threads wind down in parallel.
+pub(crate) fn stop_capture_threads(app: &AppHandle) {
+    let workers = [
+        ("Microphone transcription", app.state::<RealtimeState>().stop_worker()),
+        (
+            "System audio transcription",
+            app.state::<SystemAudioTranscriptionState>().stop_worker(),
+        ),
+        (
+            "System audio recording",
+            app.state::<SystemAudioRecordingState>().stop_worker(),
+        ),
+    ];
+
+    let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+    let mut stopped = 0;
+    let mut stuck = 0;
+    for (name, handle) in workers {
+        let Some(handle) = handle else { continue };
+        if join_until(name, handle, deadline) {
+            stopped += 1;
+        } else {
+            stuck += 1;
+        }
+    }
+
+    if stuck == 0 {
+        log::info!("Capture shutdown complete ({} thread(s) stopped)", stopped);
+    } else {
+        log::warn!(
+            "Capture shutdown timed out: {} thread(s) stopped, {} still running",
+            stopped,
+            stuck
+        );
+    }
+}
@@ -5,103 +5,210 @@ use std::collections::VecDeque;
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::time::Duration;
-use std::path::PathBuf;
-use tauri::{AppHandle, Emitter, Manager, State, Window};
+use tauri::{AppHandle, Emitter, State, Window};
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-/// Find the project root directory by looking for common markers
-fn find_project_root() -> Option<PathBuf> {
-    // Try multiple starting points
-    let starting_points = vec![
-        std::env::current_dir().ok(),
-        std::env::current_exe()
-            .ok()
-            .and_then(|p| p.parent().map(|p| p.to_path_buf())),
-    ];
-    
-    for start in starting_points.into_iter().flatten() {
-        let mut current = start;
-        
-        // Traverse up to find project root (limit to 10 levels to avoid infinite loops)
-        for _ in 0..10 {
-            // Check for common project root markers
-            let has_package_json = current.join("package.json").exists();
-            let has_models = current.join("models").exists();
-            let has_turbo_json = current.join("turbo.json").exists();
-            
-            // If we find the root package.json (not in apps/web) and models folder, we're at project root
-            if (has_package_json || has_turbo_json) && has_models {
-                return Some(current);
-            }
-            
-            // Also check if models folder exists with the model file (this is a strong indicator)
-            if has_models && current.join("models").join("ggml-base.en.bin").exists() {
-                return Some(current);
-            }
-            
-            // Go up one directory
-            match current.parent() {
-                Some(parent) => current = parent.to_path_buf(),
-                None => break,
-            }
+use crate::audio_utils::{
+    classify_speech, denoise_audio, normalize_audio, normalize_peak, peak_amplitude, resample_audio,
+    trim_silence, write_wav_file, SpeechClassification, WavBitDepth, DEFAULT_NORMALIZE_PEAK,
+    SILENCE_PEAK_THRESHOLD,
+};
+use crate::database::{create_transcription_with_segments, CreateTranscriptionInput, DbState};
+use crate::transcription::{normalize_transcript_text, session_event_name, DecodeTuning};
+
+#[cfg(target_os = "windows")]
+use wasapi::{get_default_device, Direction, SampleType, StreamMode, WaveFormat};
+
+/// Sample buffer shared between the capture thread and the transcription loop.
+///
+/// Positions are tracked as absolute sample indices since capture began, so trimming old
+/// audio never shifts the consumer's cursor and never discards samples that haven't been read.
+pub(crate) struct SampleRing {
+    samples: VecDeque<f32>,
+    /// Absolute index of `samples[0]`
+    start: u64,
+    /// Absolute index of the first sample the consumer hasn't taken yet
+    consumed: u64,
+    /// How many samples to keep around in total; only already-consumed samples are trimmed
+    max_retained: usize,
+}
+
+impl SampleRing {
+    pub(crate) fn new(max_retained: usize) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            start: 0,
+            consumed: 0,
+            max_retained,
         }
     }
-    
-    None
-}
 
-/// Resolve model path, checking bundled resources first (production), then project root (development)
-fn resolve_model_path(app: &AppHandle, model_name: &str) -> Result<PathBuf, String> {
-    // FIRST: Try bundled resources (for production builds - users won't need to download)
-    let resource_path = app.path().resource_dir()
-        .map_err(|e| format!("Failed to get resource dir: {}", e))?
-        .join("models")
-        .join(model_name);
-    
-    if resource_path.exists() {
-        return Ok(resource_path);
+    /// Append newly captured samples
+    pub(crate) fn push(&mut self, data: &[f32]) {
+        self.samples.extend(data.iter().copied());
+        self.trim();
     }
-    
-    // SECOND: Try project root models folder (for development)
-    if let Some(project_root) = find_project_root() {
-        let project_model_path = project_root.join("models").join(model_name);
-        if project_model_path.exists() {
-            return Ok(project_model_path);
-        }
+
+    /// Number of samples captured but not yet taken by the consumer
+    pub(crate) fn available(&self) -> usize {
+        (self.start + self.samples.len() as u64 - self.consumed) as usize
     }
-    
-    // THIRD: Fallback to app data directory (for user-installed models)
-    let app_data_path = app.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?
-        .join("models")
-        .join(model_name);
-    
-    if app_data_path.exists() {
-        return Ok(app_data_path);
+
+    /// Take every unread sample and advance the consumed cursor past them
+    pub(crate) fn take_available(&mut self) -> Vec<f32> {
+        self.take_with_lookback(0).0
+    }
+
+    /// Like `take_available`, but the chunk starts with up to `lookback` already-consumed
+    /// samples (as many as are still retained). Returns the chunk and that lookback count.
+    pub(crate) fn take_with_lookback(&mut self, lookback: usize) -> (Vec<f32>, usize) {
+        let offset = (self.consumed - self.start) as usize;
+        let lookback = lookback.min(offset);
+        let chunk: Vec<f32> = self.samples.range(offset - lookback..).copied().collect();
+        self.consumed += (chunk.len() - lookback) as u64;
+        self.trim();
+        (chunk, lookback)
+    }
+
+    /// Change the retention limit (e.g. once the device sample rate is known)
+    pub(crate) fn set_max_retained(&mut self, max_retained: usize) {
+        self.max_retained = max_retained;
+        self.trim();
+    }
+
+    /// Drop the oldest consumed samples beyond the retention limit
+    fn trim(&mut self) {
+        if self.samples.len() <= self.max_retained {
+            return;
+        }
+        let excess = self.samples.len() - self.max_retained;
+        let consumed_in_buffer = (self.consumed - self.start) as usize;
+        let to_remove = excess.min(consumed_in_buffer);
+        self.samples.drain(..to_remove);
+        self.start += to_remove as u64;
     }
-    
-    // If none exist, return error with all checked paths
-    Err(format!(
-        "Model file not found. Searched in:\n1. Bundled resources\n2. Project root models folder\n3. {:?}\n\nFor development: Place the model in the project root: models/{}\nFor production: The model should be bundled with the app.",
-        app_data_path,
-        model_name
-    ))
 }
 
-#[cfg(target_os = "windows")]
-use wasapi::{get_default_device, Direction, SampleType, StreamMode, WaveFormat};
+/// Seconds of consumed audio kept for lookback when no retention is configured
+const DEFAULT_BUFFER_RETENTION_SECS: u64 = 30;
+
+/// Number of samples covering `secs` seconds of audio at `sample_rate`
+pub(crate) fn retention_samples(secs: u64, sample_rate: u32) -> usize {
+    (secs as usize).saturating_mul(sample_rate as usize)
+}
+
+/// Default seconds of the previous chunk re-transcribed at the start of the next one
+const DEFAULT_OVERLAP_SECS: f32 = 0.5;
+/// Upper bound on the overlap; it has to stay well under the 3s chunk length
+const MAX_OVERLAP_SECS: f32 = 1.5;
+/// Most words compared when trimming repeated text at a chunk boundary
+pub(crate) const MAX_OVERLAP_WORDS: usize = 8;
+/// Seconds of continuous speech accumulated before the text is emitted without a pause
+const DEFAULT_MAX_ACCUMULATION_SECS: u64 = 15;
+
+/// Optional tuning for the system audio transcription loop (unset fields use their defaults)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SystemAudioConfig {
+    /// Seconds of already-transcribed audio to keep in memory (default 30)
+    pub buffer_retention_secs: Option<u64>,
+    /// Feed the previous chunk's text to Whisper as a prompt for better accuracy; default false
+    pub use_context: Option<bool>,
+    /// Anti-hallucination thresholds; unset keeps whisper's defaults
+    pub decode_tuning: Option<DecodeTuning>,
+    /// Run spectral-subtraction noise reduction on each chunk before transcribing;
+    /// helps in noisy rooms at some CPU cost; default false
+    pub denoise: Option<bool>,
+    /// Clean up spacing and capitalize sentence starts before emitting; default false
+    pub normalize_text: Option<bool>,
+    /// Seconds of the previous chunk fed again ahead of each new one so a word cut at the
+    /// boundary is heard whole; the repeated text is trimmed; default 0.5, 0 disables
+    pub overlap_secs: Option<f32>,
+    /// Skip chunks that sound like music or noise rather than speech, emitting
+    /// `non_speech_skipped` instead of transcribing them; default false
+    pub speech_only: Option<bool>,
+    /// Emit `transcription_filtered` with any text the hallucination filters dropped,
+    /// for diagnosing speech that never shows up; default false
+    pub report_filtered: Option<bool>,
+    /// Emit the accumulated text after this many seconds even if there was no pause,
+    /// so continuous speech still produces rolling captions; default 15
+    pub max_accumulation_secs: Option<u64>,
+    /// Peak level each chunk is normalized to; lower it if quiet recordings come out with
+    /// an amplified noise floor; must be in (0, 1]; default 0.8
+    pub normalize_peak: Option<f32>,
+}
+
+/// Why a piece of Whisper output was dropped instead of transcribed
+#[derive(Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+enum FilterReason {
+    Repetition,
+    TooShort,
+    SpecialToken,
+}
+
+#[derive(Serialize, Clone, Debug)]
+struct FilteredText {
+    text: String,
+    reason: FilterReason,
+}
+
+#[derive(Serialize, Clone)]
+struct NonSpeechSkipped {
+    duration_secs: f32,
+    classification: SpeechClassification,
+}
+
+impl SystemAudioConfig {
+    /// Resolve the chunk overlap in seconds, rejecting values outside 0..=MAX_OVERLAP_SECS
+    fn overlap_secs(&self) -> Result<f32, String> {
+        let overlap_secs = self.overlap_secs.unwrap_or(DEFAULT_OVERLAP_SECS);
+        if !overlap_secs.is_finite() || !(0.0..=MAX_OVERLAP_SECS).contains(&overlap_secs) {
+            return Err(format!(
+                "overlap_secs must be between 0 and {} (got {})",
+                MAX_OVERLAP_SECS, overlap_secs
+            ));
+        }
+        Ok(overlap_secs)
+    }
+}
 
 #[derive(Default)]
 pub struct SystemAudioTranscriptionState {
     running: Arc<Mutex<bool>>,
+    /// Transcription thread of the current or last session, joined on app exit
+    worker: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+impl SystemAudioTranscriptionState {
+    /// Tell the transcription thread to stop and hand back its handle for joining
+    pub(crate) fn stop_worker(&self) -> Option<thread::JoinHandle<()>> {
+        *self.running.lock().unwrap() = false;
+        self.worker.lock().unwrap().take()
+    }
 }
 
 #[derive(Default)]
 pub struct SystemAudioRecordingState {
-    recording: Arc<Mutex<bool>>,
-    audio_buffer: Arc<Mutex<Vec<f32>>>,
-    sample_rate: Arc<Mutex<Option<u32>>>,
+    pub(crate) recording: Arc<Mutex<bool>>,
+    pub(crate) audio_buffer: Arc<Mutex<Vec<f32>>>,
+    pub(crate) sample_rate: Arc<Mutex<Option<u32>>>,
+    /// Audio to transcribe instead of `audio_buffer`, at the same rate; set by mixed capture
+    /// when only some of the recorded sources should be transcribed
+    pub(crate) transcription_buffer: Arc<Mutex<Option<Vec<f32>>>>,
+    /// Recording thread (plain or mixed), joined on app exit
+    pub(crate) worker: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+impl SystemAudioRecordingState {
+    /// Tell the recording thread to stop and hand back its handle for joining
+    pub(crate) fn stop_worker(&self) -> Option<thread::JoinHandle<()>> {
+        *self.recording.lock().unwrap() = false;
+        self.worker.lock().unwrap().take()
+    }
 }
 
 /// Start real-time system audio transcription
@@ -110,7 +217,13 @@ pub async fn start_system_audio_transcription(
     app: AppHandle,
     window: Window,
     state: State<'_, SystemAudioTranscriptionState>,
-) -> Result<(), String> {
+    config: Option<SystemAudioConfig>,
+    session_id: Option<String>,
+    device_id: Option<String>,
+) -> Result<String, String> {
+    let config = config.unwrap_or_default();
+    config.overlap_secs()?;
+    normalize_peak(config.normalize_peak)?;
     let mut running = state.running.lock().unwrap();
     if *running {
         return Err("Transcription already running".into());
@@ -119,7 +232,7 @@ pub async fn start_system_audio_transcription(
 
     // Resolve model path (check project root first)
     let model_name = "ggml-base.en.bin";
-    let model_path = resolve_model_path(&app, model_name)?;
+    let model_path = crate::transcription::resolve_model_path(&app, model_name)?;
 
     let model_path_str = model_path
         .to_str()
@@ -129,20 +242,26 @@ pub async fn start_system_audio_transcription(
     let window_clone = window.clone();
     let window_error = window.clone();
     let running_clone = state.running.clone();
+    let transcription_event = session_event_name("system_audio_transcription", session_id.as_deref());
+    let error_event = session_event_name("transcription_error", session_id.as_deref());
 
     // Spawn transcription thread
-    thread::spawn(move || {
+    let worker = thread::spawn(move || {
         if let Err(err) = capture_and_transcribe_system_audio(
             window_clone,
             running_clone,
             model_path_str,
+            config,
+            session_id,
+            device_id,
         ) {
-            eprintln!("Error during system audio transcription: {:?}", err);
-            let _ = window_error.emit("transcription_error", err.to_string());
+            log::error!("System audio transcription failed: {:?}", err);
+            let _ = window_error.emit(&error_event, err.to_string());
         }
     });
+    *state.worker.lock().unwrap() = Some(worker);
 
-    Ok(())
+    Ok(transcription_event)
 }
 
 /// Stop real-time system audio transcription
@@ -160,6 +279,9 @@ fn capture_and_transcribe_system_audio(
     window: Window,
     running: Arc<Mutex<bool>>,
     model_path: String,
+    config: SystemAudioConfig,
+    session_id: Option<String>,
+    device_id: Option<String>,
 ) -> Result<()> {
     // Load Whisper model
     let ctx_params = WhisperContextParameters::default();
@@ -169,8 +291,12 @@ fn capture_and_transcribe_system_audio(
     #[cfg(not(target_os = "windows"))]
     return Err(anyhow::anyhow!("System audio capture only supported on Windows currently"));
 
-    // Audio buffer for accumulating samples
-    let audio_buffer = Arc::new(Mutex::new(Vec::<f32>::new()));
+    // Audio buffer for accumulating samples; the retention limit is sized from the real
+    // device rate once the capture thread reports it
+    let retention_secs = config
+        .buffer_retention_secs
+        .unwrap_or(DEFAULT_BUFFER_RETENTION_SECS);
+    let audio_buffer = Arc::new(Mutex::new(SampleRing::new(usize::MAX)));
     let buffer_clone = audio_buffer.clone();
     let running_clone = running.clone();
 
@@ -179,128 +305,84 @@ fn capture_and_transcribe_system_audio(
 
     // Start audio capture in a separate thread - create handles inside thread to avoid Send issues
     #[cfg(target_os = "windows")]
-    let capture_thread = thread::spawn(move || {
-        let init_result = (|| -> Result<(_, _, u32)> {
-            // Get default render (output) device for loopback capture
-            let device = get_default_device(&Direction::Render)
-                .map_err(|e| anyhow::anyhow!("Failed to get default audio device: {}", e))?;
-
-            let mut audio_client = device
-                .get_iaudioclient()
-                .map_err(|e| anyhow::anyhow!("Failed to get audio client: {}", e))?;
-
-            let device_format = audio_client
-                .get_mixformat()
-                .map_err(|e| anyhow::anyhow!("Failed to get mix format: {}", e))?;
-            let sample_rate = device_format.get_samplespersec();
-
-            // Request float32 format for easier processing
-            let desired_format = WaveFormat::new(
-                32,
-                32,
-                &SampleType::Float,
-                sample_rate as usize,
-                1, // Mono
-                None,
-            );
-
-            let (_def_time, min_time) = audio_client
-                .get_device_period()
-                .map_err(|e| anyhow::anyhow!("Failed to get device period: {}", e))?;
-
-            let mode = StreamMode::EventsShared {
-                autoconvert: true,
-                buffer_duration_hns: min_time,
-            };
-
-            audio_client
-                .initialize_client(&desired_format, &Direction::Capture, &mode)
-                .map_err(|e| anyhow::anyhow!("Failed to initialize audio client: {}", e))?;
-
-            let event_handle = audio_client
-                .set_get_eventhandle()
-                .map_err(|e| anyhow::anyhow!("Failed to set event handle: {}", e))?;
-
-            let capture_client = audio_client
-                .get_audiocaptureclient()
-                .map_err(|e| anyhow::anyhow!("Failed to get capture client: {}", e))?;
-
-            audio_client
-                .start_stream()
-                .map_err(|e| anyhow::anyhow!("Failed to start stream: {}", e))?;
-
-            Ok((event_handle, capture_client, sample_rate))
-        })();
-
-        match init_result {
-            Ok((event_handle, mut capture_client, sample_rate)) => {
-                let _ = init_tx.send(Ok(sample_rate));
-
-                loop {
-                    // Check if we should stop
-                    if !*running_clone.lock().unwrap() {
-                        break;
-                    }
-
-                    // Wait for audio data (with shorter timeout to check stop more frequently)
-                    if event_handle.wait_for_event(100).is_err() {
-                        // Check again if we should stop after timeout
-                        if !*running_clone.lock().unwrap() {
-                            break;
-                        }
-                        continue;
-                    }
+    let capture_thread = thread::spawn(move || -> Result<()> {
+        let mut capture = match open_loopback_capture(device_id.as_deref()) {
+            Ok(capture) => capture,
+            Err(e) => {
+                let _ = init_tx.send(Err(e));
+                return Ok(());
+            }
+        };
+        let sample_rate = capture.sample_rate;
+        buffer_clone
+            .lock()
+            .unwrap()
+            .set_max_retained(retention_samples(retention_secs, sample_rate));
+        let _ = init_tx.send(Ok((sample_rate, capture.format)));
+
+        let mut failures = ReadFailures::default();
+        loop {
+            // Check if we should stop
+            if !*running_clone.lock().unwrap() {
+                break;
+            }
 
-                    // Read audio data
-                    let mut temp_queue = VecDeque::new();
-                    if capture_client
-                        .read_from_device_to_deque(&mut temp_queue)
-                        .is_err()
-                    {
-                        continue;
-                    }
+            // Wait for audio data (with shorter timeout to check stop more frequently)
+            if capture.event_handle.wait_for_event(100).is_err() {
+                // Check again if we should stop after timeout
+                if !*running_clone.lock().unwrap() {
+                    break;
+                }
+                continue;
+            }
 
-                    if temp_queue.is_empty() {
-                        continue;
+            // Read audio data
+            let mut temp_queue = VecDeque::new();
+            if let Err(e) = capture
+                .capture_client
+                .read_from_device_to_deque(&mut temp_queue)
+            {
+                match failures.record(e, device_id.as_deref(), sample_rate) {
+                    Ok(Some(reopened)) => capture = reopened,
+                    Ok(None) => {}
+                    Err(e) => {
+                        // Stops the transcription loop; the error is returned from join
+                        *running_clone.lock().unwrap() = false;
+                        return Err(e);
                     }
+                }
+                continue;
+            }
+            failures.reset();
 
-                    // Convert bytes to f32 samples
-                    let mut samples = Vec::new();
-                    while temp_queue.len() >= 4 {
-                        let bytes = [
-                            temp_queue.pop_front().unwrap(),
-                            temp_queue.pop_front().unwrap(),
-                            temp_queue.pop_front().unwrap(),
-                            temp_queue.pop_front().unwrap(),
-                        ];
-                        let sample = f32::from_le_bytes(bytes);
-                        samples.push(sample);
-                    }
+            if temp_queue.is_empty() {
+                continue;
+            }
 
-                    // Add samples to buffer
-                    if !samples.is_empty() {
-                        let mut buf = buffer_clone.lock().unwrap();
-                        buf.extend(samples);
+            // Convert bytes to f32 samples
+            let samples = capture.format.drain_samples(&mut temp_queue);
 
-                        // Limit buffer size (keep last 30 seconds at 48kHz)
-                        let max_samples = 30 * 48000;
-                        if buf.len() > max_samples {
-                            let to_remove = buf.len() - max_samples;
-                            buf.drain(0..to_remove);
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                let _ = init_tx.send(Err(e));
+            // Add samples to buffer
+            if !samples.is_empty() {
+                buffer_clone.lock().unwrap().push(&samples);
             }
         }
+        Ok(())
     });
 
     // Get sample rate from capture thread
     #[cfg(target_os = "windows")]
     let sample_rate = match init_rx.recv_timeout(Duration::from_secs(5)) {
-        Ok(Ok(rate)) => rate,
+        Ok(Ok((rate, format))) => {
+            log::info!(
+                "System audio capture initialized at {}Hz as {} (retaining {}s)",
+                rate,
+                format.label(),
+                retention_secs
+            );
+            let _ = window.emit("system_audio_format", CaptureFormatInfo::new(format, rate));
+            rate
+        }
         Ok(Err(e)) => {
             return Err(anyhow::anyhow!("Failed to initialize audio capture: {}", e));
         }
@@ -312,16 +394,48 @@ fn capture_and_transcribe_system_audio(
     // Transcription loop - process audio chunks every 3 seconds
     const CHUNK_DURATION_SECS: u32 = 3;
     const TARGET_SAMPLE_RATE: u32 = 16000; // Whisper requires 16kHz
-    const SILENCE_THRESHOLD: f32 = 0.01; // Minimum audio level to process
     const PROCESSING_INTERVAL_MS: u64 = 1000; // Process every 1 second
     const SILENCE_DELAY_MS: u64 = 3000; // Wait 3 seconds of complete silence before displaying
 
-    let mut last_processed_samples = 0;
+    let transcription_event = session_event_name("system_audio_transcription", session_id.as_deref());
+    let stopped_event = session_event_name("system_audio_transcription_stopped", session_id.as_deref());
+    let skipped_event = session_event_name("non_speech_skipped", session_id.as_deref());
+    let filtered_event = session_event_name("transcription_filtered", session_id.as_deref());
+
     let mut last_displayed_chunk = String::new(); // Track last displayed chunk to avoid duplicates
     let mut last_audio_time = std::time::Instant::now();
     let mut accumulated_chunk = String::new(); // Accumulate all text into a chunk
     let mut silence_start_time: Option<std::time::Instant> = None; // Track when silence started
     let mut chunk_displayed = false; // Track if current chunk was already displayed
+    let use_context = config.use_context.unwrap_or(false);
+    let mut previous_text = String::new(); // Last transcribed text, used as prompt when use_context is set
+    let denoise = config.denoise.unwrap_or(false);
+    // Validated when the command started
+    let target_peak = normalize_peak(config.normalize_peak).unwrap_or(DEFAULT_NORMALIZE_PEAK);
+    let normalize_text = config.normalize_text.unwrap_or(false);
+    let speech_only = config.speech_only.unwrap_or(false);
+    let report_filtered = config.report_filtered.unwrap_or(false);
+    // Validated when the command started
+    let overlap_samples = (config.overlap_secs().unwrap_or(0.0) * sample_rate as f32) as usize;
+    // Only re-feed the previous chunk's tail when that chunk was speech we transcribed
+    let mut overlap_pending = false;
+    let max_accumulation = Duration::from_secs(
+        config
+            .max_accumulation_secs
+            .unwrap_or(DEFAULT_MAX_ACCUMULATION_SECS),
+    );
+    // When the current accumulation began, for the forced flush during continuous speech
+    let mut accumulation_started: Option<std::time::Instant> = None;
+    // Text emitted by the last forced flush, so the next chunk's overlap can be trimmed against it
+    let mut flushed_tail = String::new();
+    // Each displayed chunk is a complete block of speech, so it always starts a sentence
+    let tidy = |text: &str| {
+        if normalize_text {
+            normalize_transcript_text(text, Some("en"), true)
+        } else {
+            text.to_string()
+        }
+    };
 
     while *running.lock().unwrap() {
         // Check every PROCESSING_INTERVAL_MS for stop signal and processing
@@ -332,8 +446,13 @@ fn capture_and_transcribe_system_audio(
             break;
         }
 
+        // The silence flush empties the chunk; the next one starts a new accumulation
+        if accumulated_chunk.is_empty() {
+            accumulation_started = None;
+        }
+
         let mut buffer = audio_buffer.lock().unwrap();
-        let current_samples = buffer.len();
+        let available_samples = buffer.available();
 
         // Helper function to check and display chunk after silence
         let check_and_display_chunk = |accumulated_chunk: &mut String,
@@ -372,9 +491,9 @@ fn capture_and_transcribe_system_audio(
             None
         };
 
-        // Need at least CHUNK_DURATION_SECS of audio
+        // Need at least CHUNK_DURATION_SECS of NEW audio; the overlap is added on top
         let min_samples = (sample_rate * CHUNK_DURATION_SECS) as usize;
-        if current_samples < min_samples {
+        if available_samples < min_samples {
             drop(buffer);
             // Check if we should display accumulated chunk after 3 seconds of silence
             if let Some(chunk_to_display) = check_and_display_chunk(
@@ -383,48 +502,27 @@ fn capture_and_transcribe_system_audio(
                 &mut chunk_displayed,
                 &mut last_displayed_chunk,
             ) {
-                let _ = window.emit("system_audio_transcription", &chunk_to_display);
+                let chunk_to_display = tidy(&chunk_to_display);
+                crate::captions::publish_caption(&window, "system_audio", &chunk_to_display);
+                let _ = window.emit(&transcription_event, &chunk_to_display);
             }
             continue;
         }
 
-        // Only process NEW audio (no overlap to avoid duplicates)
-        let new_samples = current_samples - last_processed_samples;
-        if new_samples < min_samples {
-            drop(buffer);
-            // Check if we should display accumulated chunk after 3 seconds of silence
-            if let Some(chunk_to_display) = check_and_display_chunk(
-                &mut accumulated_chunk,
-                &mut silence_start_time,
-                &mut chunk_displayed,
-                &mut last_displayed_chunk,
-            ) {
-                let _ = window.emit("system_audio_transcription", &chunk_to_display);
-            }
-            continue;
-        }
-
-        // Take only new audio chunk (from last_processed_samples to current)
-        let chunk: Vec<f32> = buffer[last_processed_samples..current_samples].to_vec();
-
-        // Update last processed position
-        last_processed_samples = current_samples;
-
-        // Limit buffer size to prevent unbounded growth
-        if current_samples > (sample_rate * 10) as usize {
-            // Keep only last 10 seconds
-            let keep_samples = (sample_rate * 10) as usize;
-            buffer.drain(0..(current_samples - keep_samples));
-            last_processed_samples = keep_samples;
-        }
+        // Take every unread sample - the ring only trims audio that was already consumed,
+        // so samples arriving between checks are picked up on the next pass instead of lost.
+        // After speech, the tail of the previous chunk is included again so a word cut at
+        // the boundary is transcribed whole.
+        let lookback = if overlap_pending { overlap_samples } else { 0 };
+        let (chunk, lookback) = buffer.take_with_lookback(lookback);
 
         drop(buffer); // Release lock before transcription
 
         // Process audio chunk
         if !chunk.is_empty() {
-            // Check if audio has sufficient energy (not silence)
-            let max_amplitude = chunk.iter().map(|&x| x.abs()).fold(0.0f32, f32::max);
-            if max_amplitude < SILENCE_THRESHOLD {
+            // Check if the new audio has sufficient energy (not silence)
+            if peak_amplitude(&chunk[lookback..]) < SILENCE_PEAK_THRESHOLD {
+                overlap_pending = false;
                 // Audio is too quiet (silence detected)
                 // Check if we should display accumulated chunk after 3 seconds of silence
                 if let Some(chunk_to_display) = check_and_display_chunk(
@@ -433,11 +531,47 @@ fn capture_and_transcribe_system_audio(
                     &mut chunk_displayed,
                     &mut last_displayed_chunk,
                 ) {
-                    let _ = window.emit("system_audio_transcription", &chunk_to_display);
+                    let chunk_to_display = tidy(&chunk_to_display);
+                    crate::captions::publish_caption(&window, "system_audio", &chunk_to_display);
+                    let _ = window.emit(&transcription_event, &chunk_to_display);
                 }
                 continue;
             }
 
+            // Resample to 16kHz if needed
+            let processed_chunk = if sample_rate != TARGET_SAMPLE_RATE {
+                resample_audio(&chunk, sample_rate, TARGET_SAMPLE_RATE)
+            } else {
+                chunk
+            };
+
+            // Music or noise is handled like silence, so pending text is still displayed
+            if speech_only {
+                let classification = classify_speech(&processed_chunk);
+                if !classification.is_speech {
+                    log::debug!("Skipping non-speech system audio chunk: {:?}", classification);
+                    overlap_pending = false;
+                    let _ = window.emit(
+                        &skipped_event,
+                        NonSpeechSkipped {
+                            duration_secs: processed_chunk.len() as f32 / TARGET_SAMPLE_RATE as f32,
+                            classification,
+                        },
+                    );
+                    if let Some(chunk_to_display) = check_and_display_chunk(
+                        &mut accumulated_chunk,
+                        &mut silence_start_time,
+                        &mut chunk_displayed,
+                        &mut last_displayed_chunk,
+                    ) {
+                        let chunk_to_display = tidy(&chunk_to_display);
+                        crate::captions::publish_caption(&window, "system_audio", &chunk_to_display);
+                        let _ = window.emit(&transcription_event, &chunk_to_display);
+                    }
+                    continue;
+                }
+            }
+
             // Audio detected - reset silence tracking
             // If we've already displayed a chunk, start accumulating a NEW chunk
             if chunk_displayed {
@@ -450,20 +584,42 @@ fn capture_and_transcribe_system_audio(
             silence_start_time = None;
             last_audio_time = std::time::Instant::now();
 
-            // Resample to 16kHz if needed
-            let processed_chunk = if sample_rate != TARGET_SAMPLE_RATE {
-                resample_audio(&chunk, sample_rate, TARGET_SAMPLE_RATE)
+            let processed_chunk = if denoise {
+                denoise_audio(&processed_chunk)
             } else {
-                chunk
+                processed_chunk
             };
 
             // Normalize audio
-            let normalized_chunk = normalize_audio(&processed_chunk);
+            let normalized_chunk = normalize_audio(&processed_chunk, target_peak);
 
             // Transcribe and accumulate into chunk (don't emit immediately)
             // Don't pass last_transcribed_text here - we want to accumulate all unique segments
-            if let Ok(text) = transcribe_chunk_silent(&ctx, &normalized_chunk) {
-                if !text.is_empty() && !is_repetitive(&text) {
+            let prompt = if use_context {
+                Some(crate::transcription::context_prompt(&previous_text))
+            } else {
+                None
+            };
+            let mut filtered = Vec::new();
+            let result = transcribe_chunk_silent(
+                &ctx,
+                &normalized_chunk,
+                prompt,
+                config.decode_tuning.as_ref(),
+                &mut filtered,
+            );
+            if report_filtered {
+                for dropped in filtered {
+                    log::debug!("Filtered transcription output: {:?}", dropped);
+                    let _ = window.emit(&filtered_event, dropped);
+                }
+            }
+            if let Ok(text) = result {
+                overlap_pending = !text.is_empty();
+                if !text.is_empty() {
+                    if use_context {
+                        previous_text = text.clone();
+                    }
                     // Check if this text is already in accumulated_chunk to avoid duplicates
                     let text_trimmed = text.trim();
                     let accumulated_lower = accumulated_chunk.to_lowercase();
@@ -482,11 +638,32 @@ fn capture_and_transcribe_system_audio(
                     };
                     
                     if !is_duplicate {
-                        // Accumulate text into chunk
-                        if !accumulated_chunk.is_empty() {
-                            accumulated_chunk.push(' ');
+                        // Accumulate text into chunk, trimming what the overlap repeated
+                        if lookback > 0 && accumulated_chunk.is_empty() && !flushed_tail.is_empty() {
+                            accumulated_chunk = text_after_overlap(&flushed_tail, text_trimmed, MAX_OVERLAP_WORDS);
+                        } else if lookback > 0 {
+                            append_without_overlap(&mut accumulated_chunk, text_trimmed);
+                        } else {
+                            if !accumulated_chunk.is_empty() {
+                                accumulated_chunk.push(' ');
+                            }
+                            accumulated_chunk.push_str(text_trimmed);
+                        }
+                        flushed_tail.clear();
+                    }
+
+                    // Continuous speech never triggers the silence flush, so emit what has
+                    // built up once it's been accumulating long enough. The chunk is emptied,
+                    // so the silence flush has nothing left to emit twice.
+                    if !accumulated_chunk.is_empty() {
+                        let started = *accumulation_started.get_or_insert_with(std::time::Instant::now);
+                        if started.elapsed() >= max_accumulation {
+                            let chunk_to_display = tidy(accumulated_chunk.trim());
+                            crate::captions::publish_caption(&window, "system_audio", &chunk_to_display);
+                            let _ = window.emit(&transcription_event, &chunk_to_display);
+                            flushed_tail = std::mem::take(&mut accumulated_chunk);
+                            accumulation_started = None;
                         }
-                        accumulated_chunk.push_str(text_trimmed);
                     }
                 }
             }
@@ -501,23 +678,119 @@ fn capture_and_transcribe_system_audio(
         let last_normalized = last_displayed_chunk.trim().to_lowercase();
         
         if current_normalized != last_normalized {
-            let _ = window.emit("system_audio_transcription", &accumulated_chunk.trim());
+            let final_chunk = tidy(accumulated_chunk.trim());
+            crate::captions::publish_caption(&window, "system_audio", &final_chunk);
+            let _ = window.emit(&transcription_event, &final_chunk);
         }
     }
 
-    // Wait for capture thread to finish
-    let _ = capture_thread.join();
+    // Wait for capture thread to finish; it fails if the device couldn't be recovered
+    #[cfg(target_os = "windows")]
+    if let Ok(Err(e)) = capture_thread.join() {
+        return Err(e);
+    }
 
     // Emit stop event to frontend
-    let _ = window.emit("system_audio_transcription_stopped", ());
+    let _ = window.emit(&stopped_event, ());
 
     Ok(())
 }
 
+/// Lowercased alphanumerics of a word, so punctuation and casing don't block a match
+fn comparable_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Whether `old` (the end of the accumulated text) and `new` (the start of the next chunk)
+/// are the same words heard twice because of the overlap. Either end of the overlapped
+/// audio may fall mid-word, so the first new word may be a cut-off suffix of the old one,
+/// and the last old word may be a cut-off prefix of the new one.
+fn overlap_matches(old: &[&str], new: &[&str]) -> bool {
+    let last = old.len() - 1;
+    old.iter().zip(new).enumerate().all(|(i, (old_word, new_word))| {
+        let (old_word, new_word) = (comparable_word(old_word), comparable_word(new_word));
+        if old_word.is_empty() || new_word.is_empty() {
+            return false;
+        }
+        old_word == new_word
+            || (i == 0 && new_word.len() >= 2 && old_word.ends_with(&new_word))
+            || (i == last && old_word.len() >= 2 && new_word.starts_with(&old_word))
+    })
+}
+
+/// Locate the words at the start of `new_words` that repeat the end of `old_words`, as
+/// (index where the repeated words start in `old_words`, how many are repeated).
+/// Longest match first (up to `max_words`); then allow for a garbled last word before the boundary.
+fn find_overlap(old_words: &[&str], new_words: &[&str], max_words: usize) -> Option<(usize, usize)> {
+    let max_words = max_words.min(old_words.len()).min(new_words.len());
+
+    (1..=max_words)
+        .rev()
+        .find(|&k| overlap_matches(&old_words[old_words.len() - k..], &new_words[..k]))
+        .map(|k| (old_words.len() - k, k))
+        .or_else(|| {
+            let end = old_words.len().saturating_sub(1);
+            (2..=max_words.min(end))
+                .rev()
+                .find(|&k| overlap_matches(&old_words[end - k..end], &new_words[..k]))
+                .map(|k| (end - k, k))
+        })
+}
+
+/// `text` without the (at most `max_words`) words that repeat the end of already-emitted `emitted` text
+pub(crate) fn text_after_overlap(emitted: &str, text: &str, max_words: usize) -> String {
+    let old_words: Vec<&str> = emitted.split_whitespace().collect();
+    let new_words: Vec<&str> = text.split_whitespace().collect();
+    let count = find_overlap(&old_words, &new_words, max_words).map_or(0, |(_, count)| count);
+    new_words[count..].join(" ")
+}
+
+/// Append `text` to `accumulated`, dropping the words at its start that repeat the end of
+/// `accumulated` because the chunks overlap. Where a repeated word was cut off in one of
+/// the chunks the longer spelling is kept, and a garbled last word before the boundary is
+/// replaced by the text that follows it.
+fn append_without_overlap(accumulated: &mut String, text: &str) {
+    let old_words: Vec<&str> = accumulated.split_whitespace().collect();
+    let new_words: Vec<&str> = text.split_whitespace().collect();
+
+    let Some((start, count)) = find_overlap(&old_words, &new_words, MAX_OVERLAP_WORDS) else {
+        if !accumulated.is_empty() {
+            accumulated.push(' ');
+        }
+        accumulated.push_str(&new_words.join(" "));
+        return;
+    };
+
+    let repeated = old_words[start..start + count]
+        .iter()
+        .zip(&new_words[..count])
+        .map(|(old_word, new_word)| {
+            if comparable_word(new_word).len() > comparable_word(old_word).len() {
+                *new_word
+            } else {
+                *old_word
+            }
+        });
+    *accumulated = old_words[..start]
+        .iter()
+        .copied()
+        .chain(repeated)
+        .chain(new_words[count..].iter().copied())
+        .collect::<Vec<_>>()
+        .join(" ");
+}
+
 /// Transcribe an audio chunk using Whisper (silent version - returns text instead of emitting)
+/// Text dropped by the hallucination filters is pushed to `filtered` with the reason.
 fn transcribe_chunk_silent(
     ctx: &WhisperContext,
     audio_samples: &[f32],
+    prompt: Option<&str>,
+    tuning: Option<&DecodeTuning>,
+    filtered: &mut Vec<FilteredText>,
 ) -> Result<String> {
     if audio_samples.is_empty() {
         return Ok(String::new());
@@ -541,6 +814,13 @@ fn transcribe_chunk_silent(
     params.set_suppress_nst(true); // Suppress non-speech tokens to avoid hallucinations
     params.set_n_threads(4);
     params.set_max_len(0); // No limit
+    if let Some(tuning) = tuning {
+        tuning.apply(&mut params);
+    }
+    if let Some(prompt) = prompt.filter(|p| !p.is_empty()) {
+        // Sliding context: prime the decoder with the previous chunk's text
+        params.set_initial_prompt(prompt);
+    }
 
     // Process audio
     if let Ok(_) = state.full(params, audio_samples) {
@@ -550,13 +830,25 @@ fn transcribe_chunk_silent(
                 if let Ok(text) = state.full_get_segment_text(i) {
                     let text = text.trim();
                     // Filter out empty, very short, or special segments
-                    if !text.is_empty()
-                        && text.len() > 1
-                        && !text.starts_with("[_TT_")
-                        && !text.starts_with("[_")
-                    {
-                        all_text.push_str(text);
-                        all_text.push(' ');
+                    if text.is_empty() {
+                        continue;
+                    }
+                    let reason = if text.starts_with("[_") {
+                        Some(FilterReason::SpecialToken)
+                    } else if text.len() <= 1 {
+                        Some(FilterReason::TooShort)
+                    } else {
+                        None
+                    };
+                    match reason {
+                        Some(reason) => filtered.push(FilteredText {
+                            text: text.to_string(),
+                            reason,
+                        }),
+                        None => {
+                            all_text.push_str(text);
+                            all_text.push(' ');
+                        }
                     }
                 }
             }
@@ -564,11 +856,22 @@ fn transcribe_chunk_silent(
             let all_text = all_text.trim().to_string();
 
             // Filter out repetitive text only (duplicate checking happens at chunk level)
-            if !all_text.is_empty()
-                && !is_repetitive(&all_text)
-                && all_text.len() > 2
-            {
-                return Ok(all_text);
+            if all_text.is_empty() {
+                return Ok(String::new());
+            }
+            let reason = if is_repetitive(&all_text) {
+                Some(FilterReason::Repetition)
+            } else if all_text.len() <= 2 {
+                Some(FilterReason::TooShort)
+            } else {
+                None
+            };
+            match reason {
+                Some(reason) => filtered.push(FilteredText {
+                    text: all_text,
+                    reason,
+                }),
+                None => return Ok(all_text),
             }
         }
     }
@@ -612,64 +915,12 @@ fn is_repetitive(text: &str) -> bool {
     false
 }
 
-/// Normalize audio to improve transcription quality
-fn normalize_audio(input: &[f32]) -> Vec<f32> {
-    if input.is_empty() {
-        return Vec::new();
-    }
-
-    // Find maximum absolute value
-    let max_val = input
-        .iter()
-        .map(|&x| x.abs())
-        .fold(0.0f32, f32::max);
-
-    if max_val < 1e-6 {
-        // Audio is too quiet
-        return input.to_vec();
-    }
-
-    // Normalize to 0.8 peak (leave headroom)
-    let target_peak = 0.8;
-    let scale = target_peak / max_val;
-
-    input
-        .iter()
-        .map(|&x| (x * scale).clamp(-1.0, 1.0))
-        .collect()
-}
-
-/// Resample audio from one sample rate to another using linear interpolation
-fn resample_audio(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
-    if from_rate == to_rate {
-        return input.to_vec();
-    }
-
-    let ratio = to_rate as f64 / from_rate as f64;
-    let output_len = (input.len() as f64 * ratio) as usize;
-    let mut output = Vec::with_capacity(output_len);
-
-    for i in 0..output_len {
-        let src_pos = i as f64 / ratio;
-        let src_idx = src_pos as usize;
-        let frac = src_pos - src_idx as f64;
-
-        if src_idx + 1 < input.len() {
-            // Linear interpolation
-            let sample = input[src_idx] as f64 * (1.0 - frac) + input[src_idx + 1] as f64 * frac;
-            output.push(sample as f32);
-        } else if src_idx < input.len() {
-            output.push(input[src_idx]);
-        }
-    }
-
-    output
-}
-
 /// Start recording system audio (non-real-time, for later transcription)
 #[tauri::command]
 pub async fn start_system_audio_recording(
+    app: AppHandle,
     state: State<'_, SystemAudioRecordingState>,
+    device_id: Option<String>,
 ) -> Result<(), String> {
     let mut recording = state.recording.lock().unwrap();
     if *recording {
@@ -681,6 +932,7 @@ pub async fn start_system_audio_recording(
     let mut buffer = state.audio_buffer.lock().unwrap();
     buffer.clear();
     drop(buffer);
+    *state.transcription_buffer.lock().unwrap() = None;
     
     #[cfg(not(target_os = "windows"))]
     return Err("System audio recording only supported on Windows currently".into());
@@ -691,21 +943,84 @@ pub async fn start_system_audio_recording(
     
     // Start recording in a separate thread
     #[cfg(target_os = "windows")]
-    thread::spawn(move || {
-        if let Err(e) = record_system_audio(recording_clone, buffer_clone, sample_rate_clone) {
-            eprintln!("Error during system audio recording: {:?}", e);
+    let worker = thread::spawn(move || {
+        if let Err(e) = record_system_audio(
+            &app,
+            device_id.as_deref(),
+            recording_clone,
+            buffer_clone,
+            sample_rate_clone,
+        ) {
+            log::error!("System audio recording failed: {:?}", e);
+            let _ = app.emit("transcription_error", e.to_string());
         }
     });
+    #[cfg(target_os = "windows")]
+    {
+        *state.worker.lock().unwrap() = Some(worker);
+    }
     
     Ok(())
 }
 
+/// Stop recording system audio and throw the captured audio away without transcribing it
+#[tauri::command]
+pub async fn cancel_system_audio_recording(
+    app: AppHandle,
+    state: State<'_, SystemAudioRecordingState>,
+) -> Result<(), String> {
+    // Join the recording thread so it can't append after the clear
+    if let Some(worker) = state.stop_worker() {
+        let joined = tauri::async_runtime::spawn_blocking(move || worker.join())
+            .await
+            .map_err(|e| format!("Failed to wait for the recording thread: {}", e))?;
+        if joined.is_err() {
+            log::warn!("System audio recording thread panicked");
+        }
+    }
+
+    let mut buffer = state.audio_buffer.lock().unwrap();
+    let discarded = buffer.len();
+    buffer.clear();
+    buffer.shrink_to_fit();
+    drop(buffer);
+    *state.transcription_buffer.lock().unwrap() = None;
+
+    log::info!("System audio recording discarded ({} samples)", discarded);
+    let _ = app.emit("recording_discarded", ());
+
+    Ok(())
+}
+
+/// Result of stopping a recording: the transcription plus where the source audio was saved
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct RecordingTranscription {
+    pub segments: Vec<TranscriptionSegment>,
+    pub saved_path: Option<String>,
+    /// Set when the transcription was stored in the database (`save_to_db` was given)
+    pub transcription_id: Option<Uuid>,
+}
+
 /// Stop recording system audio and return the transcription segments with timestamps
+/// If `save_path` is provided, the recorded audio is also written there as a WAV file,
+/// 16-bit by default or 32-bit float with `bit_depth: 32`.
+/// If `save_to_db` is provided, a transcription for that user (optionally linked to a
+/// conversation) and all of its segments are stored in the database in a single transaction.
 #[tauri::command]
 pub async fn stop_system_audio_recording_and_transcribe(
     app: AppHandle,
     state: State<'_, SystemAudioRecordingState>,
-) -> Result<Vec<TranscriptionSegment>, String> {
+    db: State<'_, DbState>,
+    save_path: Option<String>,
+    silence_threshold: Option<f32>,
+    save_to_db: Option<CreateTranscriptionInput>,
+    bit_depth: Option<u16>,
+    tuning: Option<DecodeTuning>,
+    normalize_peak: Option<f32>,
+) -> Result<RecordingTranscription, String> {
+    let bit_depth = WavBitDepth::from_bits(bit_depth)?;
+    let target_peak = self::normalize_peak(normalize_peak)?;
+
     // Stop recording
     let mut recording = state.recording.lock().unwrap();
     *recording = false;
@@ -718,94 +1033,390 @@ pub async fn stop_system_audio_recording_and_transcribe(
     let buffer = state.audio_buffer.lock().unwrap();
     let audio_samples = buffer.clone();
     drop(buffer);
+    let transcription_samples = state.transcription_buffer.lock().unwrap().clone();
     
     let sample_rate_guard = state.sample_rate.lock().unwrap();
     let sample_rate = sample_rate_guard.unwrap_or(48000); // Default to 48kHz if not set
     drop(sample_rate_guard);
     
     if audio_samples.is_empty() {
+        log::warn!("System audio recording stopped with an empty buffer");
         return Err("No audio was recorded".into());
     }
     
-    // Resolve model path (check project root first)
+    log::info!(
+        "System audio recording stopped: {} samples at {}Hz ({:.1}s)",
+        audio_samples.len(),
+        sample_rate,
+        audio_samples.len() as f64 / sample_rate as f64
+    );
+    
+    // Save the source audio before transcribing so it survives a transcription failure
+    let saved_path = match save_path {
+        Some(path) => {
+            write_wav_file(&path, &audio_samples, sample_rate, bit_depth)
+                .map_err(|e| format!("Failed to save recording: {}", e))?;
+            Some(path)
+        }
+        None => None,
+    };
+    
+    // Load the model through the shared cache so repeated recordings don't reload it
     let model_name = "ggml-base.en.bin";
-    let model_path = resolve_model_path(&app, model_name)?;
+    let ctx = crate::transcription::get_or_load_model(&app, model_name)?;
     
-    let model_path_str = model_path
-        .to_str()
-        .ok_or("Invalid model path")?
-        .to_string();
+    // Transcribe the recorded audio (or just the chosen sources of a mixed capture) and
+    // return segments with timestamps
+    let silence_threshold = silence_threshold.unwrap_or(DEFAULT_TRIM_SILENCE_THRESHOLD);
+    let to_transcribe = transcription_samples.as_deref().unwrap_or(&audio_samples);
+    let segments = transcribe_recorded_audio(
+        &ctx,
+        to_transcribe,
+        sample_rate,
+        silence_threshold,
+        tuning.as_ref(),
+        target_peak,
+    )
+    .map_err(|e| format!("Transcription failed: {}", e))?;
+    
+    let transcription_id = match save_to_db {
+        Some(input) => {
+            let rows: Vec<(&str, f64, f64)> = segments
+                .iter()
+                .map(|s| (s.text.as_str(), s.start, s.end))
+                .collect();
+            let id = create_transcription_with_segments(&db.pool(), &input, &rows).await?;
+            log::info!("Saved recording transcription {} ({} segments)", id, rows.len());
+            Some(id)
+        }
+        None => None,
+    };
     
-    // Transcribe the recorded audio and return segments with timestamps
-    transcribe_recorded_audio(&model_path_str, &audio_samples, sample_rate)
-        .map_err(|e| format!("Transcription failed: {}", e))
+    Ok(RecordingTranscription {
+        segments,
+        saved_path,
+        transcription_id,
+    })
+}
+
+/// Sample formats requested from WASAPI, in order of preference
+#[cfg(target_os = "windows")]
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum CaptureFormat {
+    Float32,
+    Int16,
+}
+
+#[cfg(target_os = "windows")]
+impl CaptureFormat {
+    const PREFERENCE: [CaptureFormat; 2] = [CaptureFormat::Float32, CaptureFormat::Int16];
+
+    fn wave_format(self, sample_rate: u32) -> WaveFormat {
+        match self {
+            CaptureFormat::Float32 => {
+                WaveFormat::new(32, 32, &SampleType::Float, sample_rate as usize, 1, None)
+            }
+            CaptureFormat::Int16 => {
+                WaveFormat::new(16, 16, &SampleType::Int, sample_rate as usize, 1, None)
+            }
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            CaptureFormat::Float32 => "f32",
+            CaptureFormat::Int16 => "i16",
+        }
+    }
+
+    /// Convert all complete little-endian samples in the queue to f32
+    pub(crate) fn drain_samples(self, queue: &mut VecDeque<u8>) -> Vec<f32> {
+        let mut samples = Vec::new();
+        match self {
+            CaptureFormat::Float32 => {
+                while queue.len() >= 4 {
+                    let bytes = [
+                        queue.pop_front().unwrap(),
+                        queue.pop_front().unwrap(),
+                        queue.pop_front().unwrap(),
+                        queue.pop_front().unwrap(),
+                    ];
+                    samples.push(f32::from_le_bytes(bytes));
+                }
+            }
+            CaptureFormat::Int16 => {
+                while queue.len() >= 2 {
+                    let bytes = [queue.pop_front().unwrap(), queue.pop_front().unwrap()];
+                    samples.push(i16::from_le_bytes(bytes) as f32 / 32768.0);
+                }
+            }
+        }
+        samples
+    }
+}
+
+/// Payload of the `system_audio_format` event emitted once capture starts
+#[cfg(target_os = "windows")]
+#[derive(Serialize, Clone)]
+struct CaptureFormatInfo {
+    format: &'static str,
+    sample_rate: u32,
+}
+
+#[cfg(target_os = "windows")]
+impl CaptureFormatInfo {
+    fn new(format: CaptureFormat, sample_rate: u32) -> Self {
+        Self {
+            format: format.label(),
+            sample_rate,
+        }
+    }
+}
+
+/// A started loopback capture stream and the format it delivers
+#[cfg(target_os = "windows")]
+pub(crate) struct LoopbackCapture {
+    pub(crate) event_handle: wasapi::Handle,
+    pub(crate) capture_client: wasapi::AudioCaptureClient,
+    pub(crate) sample_rate: u32,
+    pub(crate) format: CaptureFormat,
+}
+
+/// An output device whose audio can be captured with loopback
+#[derive(Serialize, Debug)]
+pub struct RenderDevice {
+    pub id: String,
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// List output (render) devices; pass an `id` as `device_id` to the system audio commands
+/// to capture a device other than the default one
+#[tauri::command]
+pub async fn list_render_devices() -> Result<Vec<RenderDevice>, String> {
+    tauri::async_runtime::spawn_blocking(enumerate_render_devices)
+        .await
+        .map_err(|e| format!("Device enumeration task failed: {}", e))?
+        .map_err(|e| format!("Failed to list audio output devices: {}", e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn enumerate_render_devices() -> Result<Vec<RenderDevice>> {
+    Err(anyhow::anyhow!("System audio capture only supported on Windows currently"))
+}
+
+#[cfg(target_os = "windows")]
+fn enumerate_render_devices() -> Result<Vec<RenderDevice>> {
+    let _ = wasapi::initialize_mta();
+
+    let default_id = get_default_device(&Direction::Render)
+        .and_then(|device| device.get_id())
+        .ok();
+    let collection = wasapi::DeviceCollection::new(&Direction::Render)?;
+
+    let mut devices = Vec::new();
+    for index in 0..collection.get_nbr_devices()? {
+        let device = collection.get_device_at_index(index)?;
+        let id = device.get_id()?;
+        let name = device.get_friendlyname().unwrap_or_else(|_| id.clone());
+        devices.push(RenderDevice {
+            is_default: default_id.as_deref() == Some(id.as_str()),
+            id,
+            name,
+        });
+    }
+
+    Ok(devices)
+}
+
+/// Find the render device with the given id, falling back to the default output device
+/// when no id is given or the device is gone (e.g. unplugged since it was listed)
+#[cfg(target_os = "windows")]
+fn select_render_device(device_id: Option<&str>) -> Result<wasapi::Device> {
+    if let Some(device_id) = device_id {
+        let found = wasapi::DeviceCollection::new(&Direction::Render).and_then(|collection| {
+            for index in 0..collection.get_nbr_devices()? {
+                let device = collection.get_device_at_index(index)?;
+                if device.get_id()? == device_id {
+                    return Ok(Some(device));
+                }
+            }
+            Ok(None)
+        });
+        match found {
+            Ok(Some(device)) => return Ok(device),
+            Ok(None) => log::warn!("Audio output device {} not found, using the default device", device_id),
+            Err(e) => log::warn!("Failed to look up audio output device {}: {}, using the default device", device_id, e),
+        }
+    }
+
+    get_default_device(&Direction::Render)
+        .map_err(|e| anyhow::anyhow!("Failed to get default audio device: {}", e))
+}
+
+/// Failed loopback reads in a row before the capture client is reopened
+#[cfg(target_os = "windows")]
+const MAX_CONSECUTIVE_READ_FAILURES: u32 = 10;
+/// Reopens allowed per capture session before giving up on the device
+#[cfg(target_os = "windows")]
+const MAX_CAPTURE_REOPENS: u32 = 3;
+
+/// Counts failed loopback reads, so a glitching driver gets its capture client reopened
+/// instead of the loop silently producing no audio
+#[cfg(target_os = "windows")]
+#[derive(Default)]
+pub(crate) struct ReadFailures {
+    consecutive: u32,
+    reopens: u32,
+}
+
+#[cfg(target_os = "windows")]
+impl ReadFailures {
+    pub(crate) fn reset(&mut self) {
+        self.consecutive = 0;
+    }
+
+    /// Log a failed read. After MAX_CONSECUTIVE_READ_FAILURES in a row the capture is
+    /// reopened and the new one returned to replace the old; fails once MAX_CAPTURE_REOPENS
+    /// didn't help, or if the reopened device runs at a rate other than `sample_rate`.
+    pub(crate) fn record(
+        &mut self,
+        error: impl std::fmt::Display,
+        device_id: Option<&str>,
+        sample_rate: u32,
+    ) -> Result<Option<LoopbackCapture>> {
+        self.consecutive += 1;
+        log::warn!(
+            "System audio read failed ({} in a row): {}",
+            self.consecutive,
+            error
+        );
+        if self.consecutive < MAX_CONSECUTIVE_READ_FAILURES {
+            return Ok(None);
+        }
+        if self.reopens >= MAX_CAPTURE_REOPENS {
+            return Err(anyhow::anyhow!(
+                "System audio capture kept failing after {} reopen attempts: {}",
+                MAX_CAPTURE_REOPENS,
+                error
+            ));
+        }
+
+        self.reopens += 1;
+        self.consecutive = 0;
+        log::warn!(
+            "Reopening system audio capture (attempt {} of {})",
+            self.reopens,
+            MAX_CAPTURE_REOPENS
+        );
+        match open_loopback_capture(device_id) {
+            Ok(capture) if capture.sample_rate == sample_rate => Ok(Some(capture)),
+            Ok(capture) => Err(anyhow::anyhow!(
+                "System audio device changed from {}Hz to {}Hz while reopening; restart capture",
+                sample_rate,
+                capture.sample_rate
+            )),
+            Err(e) => {
+                // The next run of failures tries again until the reopens are used up
+                log::warn!("Failed to reopen system audio capture: {}", e);
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Start loopback capture on the chosen (or default) output device. Float32 is requested
+/// first; some drivers reject float in shared mode, so 16-bit integer is tried before giving up.
+#[cfg(target_os = "windows")]
+pub(crate) fn open_loopback_capture(device_id: Option<&str>) -> Result<LoopbackCapture> {
+    let device = select_render_device(device_id)?;
+
+    let mut last_error = None;
+    for format in CaptureFormat::PREFERENCE {
+        match open_loopback_with_format(&device, format) {
+            Ok(capture) => {
+                log::info!(
+                    "System audio capture negotiated {} at {}Hz",
+                    format.label(),
+                    capture.sample_rate
+                );
+                return Ok(capture);
+            }
+            Err(e) => {
+                log::warn!("System audio capture rejected {} format: {}", format.label(), e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No capture format available")))
+}
+
+#[cfg(target_os = "windows")]
+fn open_loopback_with_format(device: &wasapi::Device, format: CaptureFormat) -> Result<LoopbackCapture> {
+    // Use a fresh client per attempt; a client that failed to initialize can't be reused
+    let mut audio_client = device
+        .get_iaudioclient()
+        .map_err(|e| anyhow::anyhow!("Failed to get audio client: {}", e))?;
+
+    let device_format = audio_client
+        .get_mixformat()
+        .map_err(|e| anyhow::anyhow!("Failed to get mix format: {}", e))?;
+    let sample_rate = device_format.get_samplespersec();
+
+    let desired_format = format.wave_format(sample_rate);
+
+    let (_def_time, min_time) = audio_client
+        .get_device_period()
+        .map_err(|e| anyhow::anyhow!("Failed to get device period: {}", e))?;
+
+    let mode = StreamMode::EventsShared {
+        autoconvert: true,
+        buffer_duration_hns: min_time,
+    };
+
+    audio_client
+        .initialize_client(&desired_format, &Direction::Capture, &mode)
+        .map_err(|e| anyhow::anyhow!("Failed to initialize audio client: {}", e))?;
+
+    let event_handle = audio_client
+        .set_get_eventhandle()
+        .map_err(|e| anyhow::anyhow!("Failed to set event handle: {}", e))?;
+
+    let capture_client = audio_client
+        .get_audiocaptureclient()
+        .map_err(|e| anyhow::anyhow!("Failed to get capture client: {}", e))?;
+
+    audio_client
+        .start_stream()
+        .map_err(|e| anyhow::anyhow!("Failed to start stream: {}", e))?;
+
+    Ok(LoopbackCapture {
+        event_handle,
+        capture_client,
+        sample_rate,
+        format,
+    })
 }
 
 /// Record system audio to buffer
 #[cfg(target_os = "windows")]
 fn record_system_audio(
+    app: &AppHandle,
+    device_id: Option<&str>,
     recording: Arc<Mutex<bool>>,
     audio_buffer: Arc<Mutex<Vec<f32>>>,
     sample_rate: Arc<Mutex<Option<u32>>>,
 ) -> Result<()> {
-    let init_result = (|| -> Result<(_, _, u32)> {
-        // Get default render (output) device for loopback capture
-        let device = get_default_device(&Direction::Render)
-            .map_err(|e| anyhow::anyhow!("Failed to get default audio device: {}", e))?;
-        
-        let mut audio_client = device
-            .get_iaudioclient()
-            .map_err(|e| anyhow::anyhow!("Failed to get audio client: {}", e))?;
-        
-        let device_format = audio_client
-            .get_mixformat()
-            .map_err(|e| anyhow::anyhow!("Failed to get mix format: {}", e))?;
-        let sample_rate = device_format.get_samplespersec();
-        
-        // Request float32 format for easier processing
-        let desired_format = WaveFormat::new(
-            32,
-            32,
-            &SampleType::Float,
-            sample_rate as usize,
-            1, // Mono
-            None,
-        );
-        
-        let (_def_time, min_time) = audio_client
-            .get_device_period()
-            .map_err(|e| anyhow::anyhow!("Failed to get device period: {}", e))?;
-        
-        let mode = StreamMode::EventsShared {
-            autoconvert: true,
-            buffer_duration_hns: min_time,
-        };
-        
-        audio_client
-            .initialize_client(&desired_format, &Direction::Capture, &mode)
-            .map_err(|e| anyhow::anyhow!("Failed to initialize audio client: {}", e))?;
-        
-        let event_handle = audio_client
-            .set_get_eventhandle()
-            .map_err(|e| anyhow::anyhow!("Failed to set event handle: {}", e))?;
-        
-        let capture_client = audio_client
-            .get_audiocaptureclient()
-            .map_err(|e| anyhow::anyhow!("Failed to get capture client: {}", e))?;
-        
-        audio_client
-            .start_stream()
-            .map_err(|e| anyhow::anyhow!("Failed to start stream: {}", e))?;
-        
-        Ok((event_handle, capture_client, sample_rate))
-    })();
-    
-    match init_result {
-        Ok((event_handle, mut capture_client, sample_rate_value)) => {
+    match open_loopback_capture(device_id) {
+        Ok(mut capture) => {
+            let sample_rate_value = capture.sample_rate;
+            let _ = app.emit("system_audio_format", CaptureFormatInfo::new(capture.format, sample_rate_value));
             // Store sample rate
             let mut sr = sample_rate.lock().unwrap();
             *sr = Some(sample_rate_value);
             drop(sr);
+            let mut failures = ReadFailures::default();
             loop {
                 // Check if we should stop
                 if !*recording.lock().unwrap() {
@@ -813,7 +1424,7 @@ fn record_system_audio(
                 }
                 
                 // Wait for audio data
-                if event_handle.wait_for_event(100).is_err() {
+                if capture.event_handle.wait_for_event(100).is_err() {
                     if !*recording.lock().unwrap() {
                         break;
                     }
@@ -822,29 +1433,24 @@ fn record_system_audio(
                 
                 // Read audio data
                 let mut temp_queue = VecDeque::new();
-                if capture_client
+                if let Err(e) = capture
+                    .capture_client
                     .read_from_device_to_deque(&mut temp_queue)
-                    .is_err()
                 {
+                    // Giving up keeps what was recorded so far for the stop command
+                    if let Some(reopened) = failures.record(e, device_id, sample_rate_value)? {
+                        capture = reopened;
+                    }
                     continue;
                 }
+                failures.reset();
                 
                 if temp_queue.is_empty() {
                     continue;
                 }
                 
                 // Convert bytes to f32 samples
-                let mut samples = Vec::new();
-                while temp_queue.len() >= 4 {
-                    let bytes = [
-                        temp_queue.pop_front().unwrap(),
-                        temp_queue.pop_front().unwrap(),
-                        temp_queue.pop_front().unwrap(),
-                        temp_queue.pop_front().unwrap(),
-                    ];
-                    let sample = f32::from_le_bytes(bytes);
-                    samples.push(sample);
-                }
+                let samples = capture.format.drain_samples(&mut temp_queue);
                 
                 // Add samples to buffer
                 if !samples.is_empty() {
@@ -869,21 +1475,22 @@ pub struct TranscriptionSegment {
     pub end: f64,
 }
 
+/// Default amplitude below which leading/trailing audio is treated as dead air
+const DEFAULT_TRIM_SILENCE_THRESHOLD: f32 = 0.01;
+
 /// Transcribe recorded audio and return segments with timestamps
 fn transcribe_recorded_audio(
-    model_path: &str,
+    ctx: &WhisperContext,
     audio_samples: &[f32],
     sample_rate: u32,
+    silence_threshold: f32,
+    tuning: Option<&DecodeTuning>,
+    target_peak: f32,
 ) -> Result<Vec<TranscriptionSegment>> {
     if audio_samples.is_empty() {
         return Ok(Vec::new());
     }
     
-    // Load Whisper model
-    let ctx_params = WhisperContextParameters::default();
-    let ctx = WhisperContext::new_with_params(model_path, ctx_params)
-        .map_err(|e| anyhow::anyhow!("Failed to load whisper model: {:?}", e))?;
-    
     // Resample to 16kHz if needed
     const TARGET_SAMPLE_RATE: u32 = 16000;
     let processed_samples = if sample_rate != TARGET_SAMPLE_RATE {
@@ -892,8 +1499,13 @@ fn transcribe_recorded_audio(
         audio_samples.to_vec()
     };
     
+    // Drop leading/trailing dead air (saves time and avoids hallucinated segments)
+    let (trimmed_offset, trimmed_samples) =
+        trim_silence(&processed_samples, TARGET_SAMPLE_RATE, silence_threshold);
+    let time_offset = trimmed_offset as f64 / TARGET_SAMPLE_RATE as f64;
+    
     // Normalize audio
-    let normalized_samples = normalize_audio(&processed_samples);
+    let normalized_samples = normalize_audio(trimmed_samples, target_peak);
     
     // Create state and transcribe
     let mut state = ctx
@@ -912,6 +1524,9 @@ fn transcribe_recorded_audio(
     params.set_suppress_nst(true);
     params.set_n_threads(4);
     params.set_max_len(0);
+    if let Some(tuning) = tuning {
+        tuning.apply(&mut params);
+    }
     
     // Process audio
     state.full(params, &normalized_samples)
@@ -936,10 +1551,11 @@ fn transcribe_recorded_audio(
                 let end = state.full_get_segment_t1(i)
                     .map_err(|e| anyhow::anyhow!("Failed to get end time: {:?}", e))?;
                 
+                // Convert from centiseconds to seconds, relative to the untrimmed recording
                 segments.push(TranscriptionSegment {
                     text: text.to_string(),
-                    start: start as f64 / 100.0, // Convert from centiseconds to seconds
-                    end: end as f64 / 100.0,     // Convert from centiseconds to seconds
+                    start: time_offset + start as f64 / 100.0,
+                    end: time_offset + end as f64 / 100.0,
                 });
             }
         }
@@ -948,3 +1564,195 @@ fn transcribe_recorded_audio(
     Ok(segments)
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `count` consecutive samples whose values are their absolute indices from `first`
+    fn samples(first: usize, count: usize) -> Vec<f32> {
+        (first..first + count).map(|i| i as f32).collect()
+    }
+
+    #[test]
+    fn sample_ring_takes_every_sample_exactly_once() {
+        let mut ring = SampleRing::new(8);
+        let mut taken = Vec::new();
+        let mut pushed = 0;
+
+        for (i, count) in [3, 5, 0, 7, 1, 12, 4, 9].into_iter().enumerate() {
+            ring.push(&samples(pushed, count));
+            pushed += count;
+            // Consume on every other push so unread samples pile up past the limit
+            if i % 2 == 1 {
+                taken.extend(ring.take_available());
+            }
+        }
+        taken.extend(ring.take_available());
+
+        assert_eq!(taken, samples(0, pushed));
+        assert_eq!(ring.available(), 0);
+    }
+
+    #[test]
+    fn sample_ring_take_available_is_empty_when_nothing_new() {
+        let mut ring = SampleRing::new(100);
+        ring.push(&samples(0, 10));
+        assert_eq!(ring.take_available().len(), 10);
+        assert!(ring.take_available().is_empty());
+    }
+
+    #[test]
+    fn sample_ring_lookback_repeats_consumed_samples() {
+        let mut ring = SampleRing::new(100);
+        ring.push(&samples(0, 10));
+        ring.take_available();
+        ring.push(&samples(10, 5));
+
+        let (chunk, lookback) = ring.take_with_lookback(4);
+        assert_eq!(lookback, 4);
+        assert_eq!(chunk, samples(6, 9));
+        assert_eq!(ring.available(), 0);
+    }
+
+    #[test]
+    fn sample_ring_lookback_is_limited_to_consumed_samples() {
+        let mut ring = SampleRing::new(100);
+        ring.push(&samples(0, 3));
+
+        // Nothing has been consumed yet, so there is nothing to look back on
+        let (chunk, lookback) = ring.take_with_lookback(10);
+        assert_eq!(lookback, 0);
+        assert_eq!(chunk, samples(0, 3));
+    }
+
+    #[test]
+    fn sample_ring_trim_keeps_unread_samples() {
+        let mut ring = SampleRing::new(4);
+        ring.push(&samples(0, 10));
+
+        // Over the limit, but nothing is consumed, so nothing may be dropped
+        assert_eq!(ring.available(), 10);
+        assert_eq!(ring.take_available(), samples(0, 10));
+
+        // Once consumed, only the newest max_retained samples stay for lookback
+        ring.push(&samples(10, 2));
+        let (chunk, lookback) = ring.take_with_lookback(10);
+        assert_eq!(lookback, 2);
+        assert_eq!(chunk, samples(8, 4));
+    }
+
+    #[test]
+    fn sample_ring_trim_drops_only_consumed_part() {
+        let mut ring = SampleRing::new(4);
+        ring.push(&samples(0, 6));
+        ring.take_available();
+        // 6 consumed + 5 unread: the unread 5 exceed the limit on their own and all stay
+        ring.push(&samples(6, 5));
+
+        assert_eq!(ring.available(), 5);
+        let (chunk, lookback) = ring.take_with_lookback(3);
+        assert_eq!(lookback, 0);
+        assert_eq!(chunk, samples(6, 5));
+    }
+
+    #[test]
+    fn overlap_matches_cut_first_new_word() {
+        // The overlap started mid-word, so the new chunk only heard the end of "meeting"
+        assert!(overlap_matches(&["meeting", "starts"], &["eting", "starts"]));
+        // A single leftover letter is too weak to count as the same word
+        assert!(!overlap_matches(&["meeting"], &["g"]));
+    }
+
+    #[test]
+    fn overlap_matches_cut_last_old_word() {
+        // The previous chunk ended mid-word, so it only heard the start of "schedule"
+        assert!(overlap_matches(&["we", "sched"], &["we", "schedule"]));
+        // Cut-off prefixes only count at the boundary, not in the middle of the overlap
+        assert!(!overlap_matches(&["sched", "it"], &["schedule", "it"]));
+    }
+
+    #[test]
+    fn overlap_matches_ignores_case_and_punctuation() {
+        assert!(overlap_matches(&["Hello,", "world."], &["hello", "World"]));
+        assert!(!overlap_matches(&["hello", "world"], &["hello", "there"]));
+    }
+
+    #[test]
+    fn find_overlap_prefers_longest_match() {
+        let old = ["so", "the", "plan", "is", "the", "plan"];
+        let new = ["is", "the", "plan", "for", "today"];
+        assert_eq!(find_overlap(&old, &new, MAX_OVERLAP_WORDS), Some((3, 3)));
+    }
+
+    #[test]
+    fn find_overlap_skips_garbled_last_word() {
+        let old = ["let's", "talk", "about", "the", "budgxt"];
+        let new = ["about", "the", "budget", "for", "next", "year"];
+        assert_eq!(find_overlap(&old, &new, MAX_OVERLAP_WORDS), Some((2, 2)));
+    }
+
+    #[test]
+    fn find_overlap_respects_max_words() {
+        let old = ["one", "two", "three"];
+        let new = ["one", "two", "three", "four"];
+        assert_eq!(find_overlap(&old, &new, 1), None);
+        assert_eq!(find_overlap(&old, &new, 3), Some((0, 3)));
+    }
+
+    #[test]
+    fn append_without_overlap_keeps_word_cut_at_end_of_chunk_once() {
+        let mut accumulated = "the quarterly num".to_string();
+        append_without_overlap(&mut accumulated, "numbers look good");
+        assert_eq!(accumulated, "the quarterly numbers look good");
+    }
+
+    #[test]
+    fn append_without_overlap_keeps_word_cut_at_start_of_chunk_once() {
+        let mut accumulated = "we should schedule".to_string();
+        append_without_overlap(&mut accumulated, "dule a meeting");
+        assert_eq!(accumulated, "we should schedule a meeting");
+    }
+
+    #[test]
+    fn append_without_overlap_replaces_garbled_last_word() {
+        let mut accumulated = "let's talk about the budgxt".to_string();
+        append_without_overlap(&mut accumulated, "about the budget for next year");
+        assert_eq!(accumulated, "let's talk about the budget for next year");
+    }
+
+    #[test]
+    fn append_without_overlap_joins_unrelated_text() {
+        let mut accumulated = String::new();
+        append_without_overlap(&mut accumulated, "hello world");
+        append_without_overlap(&mut accumulated, "good morning");
+        assert_eq!(accumulated, "hello world good morning");
+    }
+
+    #[test]
+    fn text_after_overlap_drops_repeated_words() {
+        let emitted = "we should schedule";
+        assert_eq!(text_after_overlap(emitted, "schedule a meeting", MAX_OVERLAP_WORDS), "a meeting");
+        assert_eq!(text_after_overlap(emitted, "dule a meeting", MAX_OVERLAP_WORDS), "a meeting");
+        assert_eq!(text_after_overlap("", "a meeting", MAX_OVERLAP_WORDS), "a meeting");
+    }
+
+    #[test]
+    fn retention_samples_uses_device_rate() {
+        assert_eq!(retention_samples(30, 48_000), 1_440_000);
+        assert_eq!(retention_samples(30, 44_100), 1_323_000);
+        assert_eq!(retention_samples(0, 44_100), 0);
+    }
+
+    #[test]
+    fn set_max_retained_applies_cap() {
+        let mut ring = SampleRing::new(usize::MAX);
+        ring.push(&samples(0, 100));
+        ring.take_available();
+
+        ring.set_max_retained(retention_samples(1, 40));
+        let (chunk, lookback) = ring.take_with_lookback(usize::MAX);
+        assert_eq!(lookback, 40);
+        assert_eq!(chunk, samples(60, 40));
+    }
+}
@@ -1,11 +1,19 @@
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
-use std::sync::Mutex;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::audio_utils::{normalize_audio, read_wav_mono, read_wav_mono_bytes, resample_audio};
 
 pub struct TranscriptionState {
-    pub whisper_ctx: Mutex<Option<WhisperContext>>,
+    pub whisper_ctx: Mutex<Option<Arc<WhisperContext>>>,
     pub model_loaded: Mutex<bool>,
+    /// Loaded models keyed by model name, shared by every transcription path
+    pub model_cache: Mutex<HashMap<String, Arc<WhisperContext>>>,
+    /// Name of the cached model `whisper_ctx` points at
+    pub active_model: Mutex<Option<String>>,
 }
 
 impl Default for TranscriptionState {
@@ -13,10 +21,135 @@ impl Default for TranscriptionState {
         Self {
             whisper_ctx: Mutex::new(None),
             model_loaded: Mutex::new(false),
+            model_cache: Mutex::new(HashMap::new()),
+            active_model: Mutex::new(None),
+        }
+    }
+}
+
+impl TranscriptionState {
+    /// Context for a transcription: the named cached model if one is given, else the active one
+    fn model_for_request(&self, model_name: Option<&str>) -> Result<Arc<WhisperContext>, String> {
+        match model_name {
+            Some(name) => self
+                .model_cache
+                .lock()
+                .unwrap()
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("Model {} is not loaded. Load it with initialize_whisper first.", name)),
+            None => {
+                if !*self.model_loaded.lock().unwrap() {
+                    return Err("Whisper model not loaded. Call initialize_whisper first.".to_string());
+                }
+                self.whisper_ctx
+                    .lock()
+                    .unwrap()
+                    .clone()
+                    .ok_or_else(|| "Whisper context not available".to_string())
+            }
+        }
+    }
+
+    fn activate(&self, model_name: &str, ctx: Arc<WhisperContext>) {
+        *self.whisper_ctx.lock().unwrap() = Some(ctx);
+        *self.model_loaded.lock().unwrap() = true;
+        *self.active_model.lock().unwrap() = Some(model_name.to_string());
+    }
+}
+
+/// whisper.cpp decoding thresholds for hallucination control; unset fields keep whisper's defaults
+///
+/// Raising `no_speech_thold` or `logprob_thold`, or lowering `entropy_thold`, makes Whisper
+/// drop more low-confidence output - fewer phantom phrases in silence, but quiet or mumbled
+/// real speech is more likely to be skipped too. `temperature_inc` controls the re-decode
+/// fallback when those thresholds fail (0 disables it: faster, but no second chance).
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DecodeTuning {
+    /// Probability above which a segment is treated as silence (whisper default 0.6)
+    pub no_speech_thold: Option<f32>,
+    /// Compression-ratio-style entropy limit that triggers re-decoding (default 2.4)
+    pub entropy_thold: Option<f32>,
+    /// Average log probability below which decoding is retried (default -1.0)
+    pub logprob_thold: Option<f32>,
+    /// Temperature step for fallback re-decoding (default 0.2)
+    pub temperature_inc: Option<f32>,
+}
+
+impl DecodeTuning {
+    pub fn apply(&self, params: &mut FullParams) {
+        if let Some(value) = self.no_speech_thold {
+            params.set_no_speech_thold(value);
+        }
+        if let Some(value) = self.entropy_thold {
+            params.set_entropy_thold(value);
+        }
+        if let Some(value) = self.logprob_thold {
+            params.set_logprob_thold(value);
+        }
+        if let Some(value) = self.temperature_inc {
+            params.set_temperature_inc(value);
         }
     }
 }
 
+/// Get a model from the shared cache, loading it from disk on first use
+pub fn get_or_load_model(app: &AppHandle, model_name: &str) -> Result<Arc<WhisperContext>, String> {
+    let state = app.state::<TranscriptionState>();
+    
+    if let Some(ctx) = state.model_cache.lock().unwrap().get(model_name) {
+        return Ok(ctx.clone());
+    }
+    
+    // Load without holding the cache lock - large models can take a while
+    let model_path = resolve_model_path(app, model_name)?;
+    let ctx = WhisperContext::new_with_params(
+        model_path.to_str().ok_or("Invalid model path")?,
+        WhisperContextParameters::default(),
+    )
+    .map_err(|e| format!("Failed to load whisper model: {:?}", e))?;
+    
+    let ctx = Arc::new(ctx);
+    state
+        .model_cache
+        .lock()
+        .unwrap()
+        .insert(model_name.to_string(), ctx.clone());
+    
+    Ok(ctx)
+}
+
+/// Load a model from an in-memory ggml buffer into the shared cache under `model_name`,
+/// replacing any model cached with that name. For builds that embed the model with
+/// `include_bytes!` instead of shipping a file.
+pub fn load_model_from_bytes(
+    app: &AppHandle,
+    model_name: &str,
+    model_bytes: &[u8],
+) -> Result<Arc<WhisperContext>, String> {
+    let ctx = WhisperContext::new_from_buffer_with_params(model_bytes, WhisperContextParameters::default())
+        .map_err(|e| format!("Failed to load whisper model from memory: {:?}", e))?;
+
+    let ctx = Arc::new(ctx);
+    app.state::<TranscriptionState>()
+        .model_cache
+        .lock()
+        .unwrap()
+        .insert(model_name.to_string(), ctx.clone());
+
+    Ok(ctx)
+}
+
+/// True if `candidate` exists and was modified after `other`
+fn is_newer(candidate: &std::path::Path, other: &std::path::Path) -> bool {
+    let modified = |path: &std::path::Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(candidate), modified(other)) {
+        (Some(candidate), Some(other)) => candidate > other,
+        _ => false,
+    }
+}
+
 /// Find the project root directory by looking for common markers (like Cargo.toml, package.json, etc.)
 fn find_project_root() -> Option<PathBuf> {
     // Try multiple starting points
@@ -58,43 +191,90 @@ fn find_project_root() -> Option<PathBuf> {
     None
 }
 
-/// Resolve model path, checking bundled resources first (production), then project root (development)
-fn resolve_model_path(app: &AppHandle, model_name: &str) -> Result<PathBuf, String> {
-    let mut checked_paths = Vec::new();
-    
-    // FIRST: Try bundled resources (for production builds - users won't need to download)
-    let resource_path = app.path().resource_dir()
-        .map_err(|e| format!("Failed to get resource dir: {}", e))?
-        .join("models")
-        .join(model_name);
-    checked_paths.push(format!("1. Bundled resources: {:?}", resource_path));
-    
-    if resource_path.exists() {
-        return Ok(resource_path);
+/// The places a model is searched for, in order: bundled resources (production), the project
+/// root `models/` folder (development), then the writable app data `models/` folder
+struct ModelSearch {
+    bundled: PathBuf,
+    project_root: Option<PathBuf>,
+    app_data: PathBuf,
+}
+
+impl ModelSearch {
+    fn new(app: &AppHandle, model_name: &str) -> Result<Self, String> {
+        let bundled = crate::paths::resource_dir(app)?
+            .join("models")
+            .join(model_name);
+        let app_data = crate::paths::models_dir(app)?.join(model_name);
+        let project_root = find_project_root().map(|root| root.join("models").join(model_name));
+
+        Ok(Self {
+            bundled,
+            project_root,
+            app_data,
+        })
     }
-    
-    // SECOND: Try project root models folder (for development)
-    if let Some(project_root) = find_project_root() {
-        let project_model_path = project_root.join("models").join(model_name);
-        checked_paths.push(format!("2. Project root: {:?}", project_model_path));
-        if project_model_path.exists() {
-            return Ok(project_model_path);
+
+    /// The path to load, or None if the model isn't in any location
+    /// A copy in app data wins over the bundled one when it's newer, so a downloaded model
+    /// can replace the bundled file without reinstalling.
+    fn resolve(&self) -> Option<&PathBuf> {
+        if self.bundled.exists() {
+            if is_newer(&self.app_data, &self.bundled) {
+                return Some(&self.app_data);
+            }
+            return Some(&self.bundled);
         }
-    } else {
-        checked_paths.push("2. Project root: (could not determine project root)".to_string());
+        if let Some(project_path) = self.project_root.as_ref().filter(|p| p.exists()) {
+            return Some(project_path);
+        }
+        if self.app_data.exists() {
+            return Some(&self.app_data);
+        }
+        None
     }
-    
-    // THIRD: Fallback to app data directory (for user-installed models)
-    let app_data_path = app.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?
-        .join("models")
-        .join(model_name);
-    checked_paths.push(format!("3. App data dir: {:?}", app_data_path));
-    
-    if app_data_path.exists() {
-        return Ok(app_data_path);
+
+    /// Every location in search order, whether or not it was reached
+    fn checks(&self) -> Vec<ModelPathCheck> {
+        let check = |location: &str, path: Option<&PathBuf>| ModelPathCheck {
+            location: location.to_string(),
+            path: path.map(|p| p.to_string_lossy().to_string()),
+            exists: path.is_some_and(|p| p.exists()),
+        };
+        vec![
+            check("Bundled resources", Some(&self.bundled)),
+            check("Project root", self.project_root.as_ref()),
+            check("App data dir", Some(&self.app_data)),
+        ]
     }
-    
+}
+
+/// Resolve model path, checking bundled resources first (production), then project root (development)
+/// A copy in the writable app data `models/` folder wins over the bundled one when it's newer,
+/// so a downloaded model can replace the bundled file without reinstalling.
+pub(crate) fn resolve_model_path(app: &AppHandle, model_name: &str) -> Result<PathBuf, String> {
+    let search = ModelSearch::new(app, model_name)?;
+
+    if let Some(path) = search.resolve() {
+        if *path == search.app_data && search.bundled.exists() {
+            log::info!("Using app data model {:?} (newer than bundled copy)", path);
+        } else if *path == search.bundled {
+            log::info!("Using bundled model {:?}", path);
+        } else if *path == search.app_data {
+            log::info!("Using app data model {:?}", path);
+        }
+        return Ok(path.clone());
+    }
+
+    let checked_paths: Vec<String> = search
+        .checks()
+        .iter()
+        .enumerate()
+        .map(|(i, check)| match &check.path {
+            Some(path) => format!("{}. {}: {:?}", i + 1, check.location, path),
+            None => format!("{}. {}: (could not determine project root)", i + 1, check.location),
+        })
+        .collect();
+
     // If none exist, return error with all checked paths
     Err(format!(
         "Model file not found. Searched in:\n{}\n\nFor development: Place the model in the project root: models/{}\nFor production: The model should be bundled with the app.\n\nCurrent directory: {:?}\nExecutable path: {:?}",
@@ -105,39 +285,207 @@ fn resolve_model_path(app: &AppHandle, model_name: &str) -> Result<PathBuf, Stri
     ))
 }
 
+#[derive(serde::Serialize)]
+pub struct ModelPathCheck {
+    pub location: String,
+    /// None when the location couldn't be determined (e.g. no project root in production)
+    pub path: Option<String>,
+    pub exists: bool,
+}
+
+#[derive(serde::Serialize)]
+pub struct ModelResolutionReport {
+    pub model_name: String,
+    /// Every location `resolve_model_path` checks, in search order
+    pub checked: Vec<ModelPathCheck>,
+    /// The path that would be loaded, if any
+    pub resolved: Option<String>,
+    pub current_dir: Option<String>,
+    pub executable_path: Option<String>,
+}
+
+/// Run the model lookup without loading anything and report every path that was checked,
+/// so "Model file not found" reports can include exactly where the app looked
+#[tauri::command]
+pub async fn debug_model_resolution(
+    app: AppHandle,
+    model_name: String,
+) -> Result<ModelResolutionReport, String> {
+    let search = ModelSearch::new(&app, &model_name)?;
+
+    Ok(ModelResolutionReport {
+        checked: search.checks(),
+        resolved: search.resolve().map(|p| p.to_string_lossy().to_string()),
+        current_dir: std::env::current_dir()
+            .ok()
+            .map(|p| p.to_string_lossy().to_string()),
+        executable_path: std::env::current_exe()
+            .ok()
+            .map(|p| p.to_string_lossy().to_string()),
+        model_name,
+    })
+}
+
+/// Mean log-probability of a segment's text tokens (special tokens skipped). Values closer
+/// to 0 mean Whisper was more confident; None if the segment has no text tokens.
+pub(crate) fn segment_avg_logprob(
+    ctx: &WhisperContext,
+    state: &WhisperState,
+    segment: i32,
+) -> Option<f32> {
+    let n_tokens = state.full_n_tokens(segment).ok()?;
+    let eot = ctx.token_eot();
+    let logprobs: Vec<f32> = (0..n_tokens)
+        .filter_map(|token| state.full_get_token_data(segment, token).ok())
+        .filter(|data| data.id < eot)
+        .map(|data| data.plog)
+        .collect();
+    if logprobs.is_empty() {
+        return None;
+    }
+    Some(logprobs.iter().sum::<f32>() / logprobs.len() as f32)
+}
+
+/// Bundled model used when a requested model is missing and fallback is allowed
+const FALLBACK_MODEL_NAME: &str = "ggml-base.en.bin";
+
+/// How long `initialize_whisper` waits for a model to load before giving up
+const DEFAULT_MODEL_LOAD_TIMEOUT_SECS: u64 = 120;
+
+#[derive(serde::Serialize, Clone)]
+struct ModelLoadProgress {
+    model_name: String,
+    elapsed_ms: u64,
+}
+
+/// Load a model off the async runtime, emitting `model_loading` every second until it's
+/// ready and `model_loaded` when done. On timeout the load keeps running in the background
+/// and lands in the cache, so a retry picks it up.
+async fn load_model_with_progress(
+    app: &AppHandle,
+    model_name: &str,
+    timeout: Duration,
+) -> Result<Arc<WhisperContext>, String> {
+    let state = app.state::<TranscriptionState>();
+    if let Some(ctx) = state.model_cache.lock().unwrap().get(model_name) {
+        return Ok(ctx.clone());
+    }
+
+    let started = Instant::now();
+    let progress = |started: Instant| ModelLoadProgress {
+        model_name: model_name.to_string(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    };
+
+    let mut load = tokio::task::spawn_blocking({
+        let app = app.clone();
+        let model_name = model_name.to_string();
+        move || get_or_load_model(&app, &model_name)
+    });
+    let deadline = tokio::time::sleep(timeout);
+    tokio::pin!(deadline);
+    let mut ticker = tokio::time::interval(Duration::from_secs(1));
+
+    let ctx = loop {
+        tokio::select! {
+            result = &mut load => {
+                break result.map_err(|e| format!("Model loading task failed: {}", e))??;
+            }
+            _ = ticker.tick() => {
+                let _ = app.emit("model_loading", progress(started));
+            }
+            _ = &mut deadline => {
+                return Err(format!(
+                    "Timed out after {}s loading model {}. The model may be too large for this machine; try a smaller one.",
+                    timeout.as_secs(),
+                    model_name
+                ));
+            }
+        }
+    };
+
+    log::info!("Loaded whisper model {} in {:?}", model_name, started.elapsed());
+    let _ = app.emit("model_loaded", progress(started));
+    Ok(ctx)
+}
+
 #[tauri::command]
 pub async fn initialize_whisper(
     app: AppHandle,
     model_name: String,
+    fallback: Option<bool>,
+    timeout_secs: Option<u64>,
 ) -> Result<String, String> {
     let state = app.state::<TranscriptionState>();
     
-    let model_path = resolve_model_path(&app, &model_name)?;
+    let (model_name, model_path) = match resolve_model_path(&app, &model_name) {
+        Ok(path) => (model_name, path),
+        Err(e) if fallback.unwrap_or(false) && model_name != FALLBACK_MODEL_NAME => {
+            let path = resolve_model_path(&app, FALLBACK_MODEL_NAME).map_err(|fallback_err| {
+                format!("{}\n\nFallback model {} is also unavailable: {}", e, FALLBACK_MODEL_NAME, fallback_err)
+            })?;
+            log::warn!(
+                "Whisper model {} not found, falling back to bundled {}",
+                model_name,
+                FALLBACK_MODEL_NAME
+            );
+            (FALLBACK_MODEL_NAME.to_string(), path)
+        }
+        Err(e) => return Err(e),
+    };
     
-    // Load the model
-    let ctx_params = WhisperContextParameters::default();
-    let ctx = WhisperContext::new_with_params(
-        model_path.to_str().ok_or("Invalid model path")?,
-        ctx_params
-    )
-    .map_err(|e| format!("Failed to load whisper model: {:?}", e))?;
+    // Load the model (reuses the cached context if it was loaded before)
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_MODEL_LOAD_TIMEOUT_SECS));
+    let ctx = load_model_with_progress(&app, &model_name, timeout).await?;
     
-    *state.whisper_ctx.lock().unwrap() = Some(ctx);
-    *state.model_loaded.lock().unwrap() = true;
+    state.activate(&model_name, ctx);
     
-    Ok(format!("Model loaded successfully from: {:?}", model_path))
+    Ok(format!("Model {} loaded successfully from: {:?}", model_name, model_path))
+}
+
+/// Initialize Whisper from the model file's bytes rather than a path, and make it active
+/// The model is cached under `model_name` like a file-loaded one, so `transcribe_*` calls
+/// can select it by that name.
+#[tauri::command]
+pub async fn initialize_whisper_from_bytes(
+    app: AppHandle,
+    model_bytes: Vec<u8>,
+    model_name: String,
+) -> Result<String, String> {
+    if model_name.trim().is_empty() {
+        return Err("Model name is empty".to_string());
+    }
+    if model_bytes.is_empty() {
+        return Err("Model data is empty".to_string());
+    }
+
+    let started = Instant::now();
+    let size = model_bytes.len();
+    let ctx = tokio::task::spawn_blocking({
+        let app = app.clone();
+        let model_name = model_name.clone();
+        move || load_model_from_bytes(&app, &model_name, &model_bytes)
+    })
+    .await
+    .map_err(|e| format!("Model loading task failed: {}", e))??;
+
+    app.state::<TranscriptionState>().activate(&model_name, ctx);
+
+    log::info!(
+        "Loaded whisper model {} from memory ({} bytes) in {:?}",
+        model_name,
+        size,
+        started.elapsed()
+    );
+    Ok(format!("Model {} loaded successfully from memory", model_name))
 }
 
 #[tauri::command]
 pub async fn get_model_paths(app: AppHandle) -> Result<ModelPaths, String> {
-    let resource_dir = app.path().resource_dir()
-        .map_err(|e| format!("Failed to get resource dir: {}", e))?;
-    
-    let app_data_dir = app.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let resource_dir = crate::paths::resource_dir(&app)?;
     
     let resource_models = resource_dir.join("models");
-    let app_data_models = app_data_dir.join("models");
+    let app_data_models = crate::paths::models_dir(&app)?;
     
     Ok(ModelPaths {
         resource_dir: resource_models.to_string_lossy().to_string(),
@@ -153,13 +501,12 @@ pub async fn transcribe_audio(
     app: AppHandle,
     audio_path: String,
     language: Option<String>,
+    tuning: Option<DecodeTuning>,
+    active_model: Option<String>,
 ) -> Result<String, String> {
     let state = app.state::<TranscriptionState>();
     
-    let model_loaded = *state.model_loaded.lock().unwrap();
-    if !model_loaded {
-        return Err("Whisper model not loaded. Call initialize_whisper first.".to_string());
-    }
+    let ctx = state.model_for_request(active_model.as_deref())?;
     
     let mut reader = hound::WavReader::open(&audio_path)
         .map_err(|e| format!("Failed to open WAV: {}", e))?;
@@ -190,9 +537,7 @@ pub async fn transcribe_audio(
     params.set_print_realtime(false);
     params.set_print_timestamps(false);
     params.set_n_threads(4);
-    
-    let ctx_guard = state.whisper_ctx.lock().unwrap();
-    let ctx = ctx_guard.as_ref().ok_or("Whisper context not available")?;
+    tuning.unwrap_or_default().apply(&mut params);
     
     let mut whisper_state = ctx.create_state()
         .map_err(|e| format!("Failed to create state: {:?}", e))?;
@@ -218,13 +563,12 @@ pub async fn transcribe_audio_with_timestamps(
     app: AppHandle,
     audio_path: String,
     language: Option<String>,
+    tuning: Option<DecodeTuning>,
+    active_model: Option<String>,
 ) -> Result<Vec<TranscriptionSegment>, String> {
     let state = app.state::<TranscriptionState>();
     
-    let model_loaded = *state.model_loaded.lock().unwrap();
-    if !model_loaded {
-        return Err("Whisper model not loaded. Call initialize_whisper first.".to_string());
-    }
+    let ctx = state.model_for_request(active_model.as_deref())?;
     
     let mut reader = hound::WavReader::open(&audio_path)
         .map_err(|e| format!("Failed to open WAV: {}", e))?;
@@ -251,9 +595,7 @@ pub async fn transcribe_audio_with_timestamps(
     params.set_print_realtime(false);
     params.set_print_timestamps(true);
     params.set_n_threads(4);
-    
-    let ctx_guard = state.whisper_ctx.lock().unwrap();
-    let ctx = ctx_guard.as_ref().ok_or("Whisper context not available")?;
+    tuning.unwrap_or_default().apply(&mut params);
     
     let mut whisper_state = ctx.create_state()
         .map_err(|e| format!("Failed to create state: {:?}", e))?;
@@ -283,6 +625,222 @@ pub async fn transcribe_audio_with_timestamps(
     Ok(segments)
 }
 
+/// Transcribe a 16kHz WAV and return every decoded token with its timing and probability
+/// Special tokens (timestamps, [_BEG_], end-of-text, ...) are included and flagged so the
+/// caller can build karaoke-style highlighting or confidence coloring from the rest.
+#[tauri::command]
+pub async fn transcribe_audio_tokens(
+    app: AppHandle,
+    audio_path: String,
+    language: Option<String>,
+    tuning: Option<DecodeTuning>,
+    active_model: Option<String>,
+) -> Result<Vec<WhisperToken>, String> {
+    let state = app.state::<TranscriptionState>();
+    
+    let ctx = state.model_for_request(active_model.as_deref())?;
+    
+    let mut reader = hound::WavReader::open(&audio_path)
+        .map_err(|e| format!("Failed to open WAV: {}", e))?;
+    
+    let spec = reader.spec();
+    if spec.sample_rate != 16000 {
+        return Err(format!("Audio must be 16kHz, got {}Hz", spec.sample_rate));
+    }
+    
+    let audio_data: Vec<f32> = reader
+        .samples::<i16>()
+        .map(|s| s.unwrap_or(0) as f32 / i16::MAX as f32)
+        .collect();
+    
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    
+    if let Some(ref lang) = language {
+        params.set_language(Some(lang.as_str()));
+    }
+    
+    params.set_translate(false);
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_token_timestamps(true); // Without this t0/t1 are only segment-level
+    params.set_n_threads(4);
+    tuning.unwrap_or_default().apply(&mut params);
+    
+    let mut whisper_state = ctx.create_state()
+        .map_err(|e| format!("Failed to create state: {:?}", e))?;
+    
+    whisper_state.full(params, &audio_data)
+        .map_err(|e| format!("Transcription failed: {:?}", e))?;
+    
+    let num_segments = whisper_state.full_n_segments()
+        .map_err(|e| format!("Failed to get segments: {:?}", e))?;
+    
+    let eot = ctx.token_eot();
+    let mut tokens = Vec::new();
+    for segment in 0..num_segments {
+        let n_tokens = whisper_state.full_n_tokens(segment)
+            .map_err(|e| format!("Failed to get token count: {:?}", e))?;
+        for token in 0..n_tokens {
+            let token_text = whisper_state.full_get_token_text_lossy(segment, token)
+                .map_err(|e| format!("Failed to get token text: {:?}", e))?;
+            let data = whisper_state.full_get_token_data(segment, token)
+                .map_err(|e| format!("Failed to get token data: {:?}", e))?;
+            
+            tokens.push(WhisperToken {
+                token_text,
+                t0: data.t0 as f64 / 100.0,
+                t1: data.t1 as f64 / 100.0,
+                prob: data.p,
+                // Every id from end-of-text upwards is a control or timestamp token
+                is_special: data.id >= eot,
+            });
+        }
+    }
+    
+    Ok(tokens)
+}
+
+/// Re-run a full-file transcription of a saved recording, e.g. with a bigger model or another language
+/// Accepts any WAV sample rate / channel layout; audio is downmixed and resampled to 16kHz
+#[tauri::command]
+pub async fn retranscribe_file(
+    app: AppHandle,
+    path: String,
+    language: Option<String>,
+    model_name: Option<String>,
+    tuning: Option<DecodeTuning>,
+    normalize_peak: Option<f32>,
+) -> Result<Vec<TranscriptionSegment>, String> {
+    let target_peak = crate::audio_utils::normalize_peak(normalize_peak)?;
+    let model_name = model_name.unwrap_or_else(|| "ggml-base.en.bin".to_string());
+    let ctx = get_or_load_model(&app, &model_name)?;
+    
+    let (samples, sample_rate) = read_wav_mono(&path)?;
+    transcribe_full_audio(&ctx, &samples, sample_rate, language.as_deref(), tuning, target_peak)
+}
+
+/// Transcribe a base64-encoded WAV (e.g. a blob recorded in the web view) with segment timings
+/// Like `transcribe_audio_with_timestamps`, but any sample rate / channel layout is accepted
+#[tauri::command]
+pub async fn transcribe_audio_local_with_timestamps(
+    app: AppHandle,
+    audio_base64: String,
+    language: Option<String>,
+    normalize_peak: Option<f32>,
+) -> Result<Vec<TranscriptionSegment>, String> {
+    use base64::Engine;
+
+    let target_peak = crate::audio_utils::normalize_peak(normalize_peak)?;
+    let wav = base64::engine::general_purpose::STANDARD
+        .decode(audio_base64)
+        .map_err(|e| format!("Failed to decode base64 audio: {}", e))?;
+    let ctx = get_or_load_model(&app, "ggml-base.en.bin")?;
+    
+    let (samples, sample_rate) = read_wav_mono_bytes(&wav)?;
+    transcribe_full_audio(&ctx, &samples, sample_rate, language.as_deref(), None, target_peak)
+}
+
+/// Transcribe a whole recording in one pass: mono samples at `sample_rate` are resampled to
+/// 16kHz and normalized to `target_peak`, and timestamp-only segments are dropped
+fn transcribe_full_audio(
+    ctx: &WhisperContext,
+    samples: &[f32],
+    sample_rate: u32,
+    language: Option<&str>,
+    tuning: Option<DecodeTuning>,
+    target_peak: f32,
+) -> Result<Vec<TranscriptionSegment>, String> {
+    if samples.is_empty() {
+        return Err("Audio file is empty".to_string());
+    }
+    
+    let samples = resample_audio(samples, sample_rate, 16000);
+    let samples = normalize_audio(&samples, target_peak);
+    
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some(language.unwrap_or("en")));
+    params.set_translate(false);
+    params.set_no_context(false); // Full file - context improves accuracy
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_suppress_blank(true);
+    params.set_suppress_nst(true);
+    params.set_n_threads(4);
+    tuning.unwrap_or_default().apply(&mut params);
+    
+    let mut whisper_state = ctx.create_state()
+        .map_err(|e| format!("Failed to create state: {:?}", e))?;
+    
+    whisper_state.full(params, &samples)
+        .map_err(|e| format!("Transcription failed: {:?}", e))?;
+    
+    let num_segments = whisper_state.full_n_segments()
+        .map_err(|e| format!("Failed to get segments: {:?}", e))?;
+    
+    let mut segments = Vec::new();
+    for i in 0..num_segments {
+        let text = whisper_state.full_get_segment_text(i)
+            .map_err(|e| format!("Failed to get segment: {:?}", e))?;
+        let text = text.trim();
+        if text.is_empty() || text.starts_with("[_") {
+            continue;
+        }
+        let start = whisper_state.full_get_segment_t0(i)
+            .map_err(|e| format!("Failed to get start time: {:?}", e))?;
+        let end = whisper_state.full_get_segment_t1(i)
+            .map_err(|e| format!("Failed to get end time: {:?}", e))?;
+        
+        segments.push(TranscriptionSegment {
+            text: text.to_string(),
+            start: start as f64 / 100.0,
+            end: end as f64 / 100.0,
+        });
+    }
+    
+    Ok(segments)
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct LoadedModel {
+    pub name: String,
+    pub active: bool,
+}
+
+/// Models currently held in the shared cache, so the UI can switch between them instantly
+#[tauri::command]
+pub async fn list_loaded_models(app: AppHandle) -> Result<Vec<LoadedModel>, String> {
+    let state = app.state::<TranscriptionState>();
+    let active = state.active_model.lock().unwrap().clone();
+
+    let mut models: Vec<LoadedModel> = state
+        .model_cache
+        .lock()
+        .unwrap()
+        .keys()
+        .map(|name| LoadedModel {
+            name: name.clone(),
+            active: active.as_deref() == Some(name.as_str()),
+        })
+        .collect();
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(models)
+}
+
+/// Make an already-loaded model the one transcription commands use by default
+/// Fails if the model isn't in the cache; load it with `initialize_whisper` first.
+#[tauri::command]
+pub async fn set_active_model(app: AppHandle, model_name: String) -> Result<(), String> {
+    let state = app.state::<TranscriptionState>();
+    let ctx = state.model_for_request(Some(&model_name))?;
+    state.activate(&model_name, ctx);
+    log::info!("Active whisper model set to {}", model_name);
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn check_whisper_status(app: AppHandle) -> Result<WhisperStatus, String> {
     let state = app.state::<TranscriptionState>();
@@ -309,13 +867,452 @@ pub async fn get_model_path(app: AppHandle) -> Result<String, String> {
     }
     
     // Fallback to app data directory
-    let app_data_dir = app.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
-    
-    let model_dir = app_data_dir.join("models");
+    let model_dir = crate::paths::models_dir(&app)?;
     Ok(model_dir.to_string_lossy().to_string())
 }
 
+/// Event name for a transcription session: `base` alone, or `base-{session_id}` so several
+/// windows can run independent sessions without hearing each other's events
+pub(crate) fn session_event_name(base: &str, session_id: Option<&str>) -> String {
+    match session_id.map(str::trim).filter(|id| !id.is_empty()) {
+        Some(id) => format!("{}-{}", base, id),
+        None => base.to_string(),
+    }
+}
+
+/// Longest prompt (in characters) fed back to Whisper as context from the previous chunk
+const CONTEXT_PROMPT_MAX_CHARS: usize = 200;
+
+/// Tail of the previous chunk's text to use as the next chunk's initial prompt
+/// Whisper only looks at the last ~224 prompt tokens, so older text is dropped.
+pub(crate) fn context_prompt(previous_text: &str) -> &str {
+    let text = previous_text.trim();
+    let char_count = text.chars().count();
+    if char_count <= CONTEXT_PROMPT_MAX_CHARS {
+        return text;
+    }
+    let start = text
+        .char_indices()
+        .nth(char_count - CONTEXT_PROMPT_MAX_CHARS)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    text[start..].trim_start()
+}
+
+/// Remove and return every complete sentence from `buffer`, leaving any unfinished tail
+/// A sentence ends at `.`, `!`, `?` or `…` followed by whitespace or the end of the text,
+/// so decimals like "3.5" and ellipses like "..." mid-thought don't split.
+pub(crate) fn take_complete_sentences(buffer: &mut String) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = buffer.char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?' | '…') {
+            continue;
+        }
+        if chars.peek().map_or(true, |&(_, next)| next.is_whitespace()) {
+            let end = idx + c.len_utf8();
+            let sentence = buffer[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence.to_string());
+            }
+            start = end;
+        }
+    }
+
+    *buffer = buffer[start..].trim_start().to_string();
+    sentences
+}
+
+/// Languages that put a space before `! ? ; :` (e.g. "Quoi ?"), which must be preserved
+const SPACED_PUNCTUATION_LANGUAGES: [&str; 1] = ["fr"];
+
+/// Tidy Whisper output: collapse runs of whitespace, drop stray spaces before punctuation and
+/// capitalize sentence starts (after `.`, `!`, `?` or `…` and whitespace). `starts_sentence` says whether the text begins a new sentence
+/// (real-time chunks often continue the previous one). Scripts without letter case are left
+/// as they are, and French keeps its space before `! ? ; :`.
+pub(crate) fn normalize_transcript_text(
+    text: &str,
+    language: Option<&str>,
+    starts_sentence: bool,
+) -> String {
+    let spaced_punctuation = language
+        .map(|l| SPACED_PUNCTUATION_LANGUAGES.contains(&l.to_lowercase().as_str()))
+        .unwrap_or(false);
+    let tight_punctuation = |c: char| match c {
+        ',' | '.' | '…' | ')' | ']' => true,
+        '!' | '?' | ';' | ':' => !spaced_punctuation,
+        _ => false,
+    };
+
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut normalized = String::with_capacity(collapsed.len());
+    let mut capitalize_next = starts_sentence;
+    // Whether the text so far ends in terminal punctuation (plus any closing quotes/brackets);
+    // only whitespace after it starts a new sentence, so "e.g." and "example.com" stay intact
+    let mut after_terminal = false;
+
+    for c in collapsed.chars() {
+        if tight_punctuation(c) && normalized.ends_with(' ') {
+            normalized.pop();
+        }
+
+        if c.is_alphanumeric() {
+            if capitalize_next && c.is_lowercase() {
+                normalized.extend(c.to_uppercase());
+            } else {
+                normalized.push(c);
+            }
+            capitalize_next = false;
+        } else {
+            normalized.push(c);
+            if c.is_whitespace() && after_terminal {
+                capitalize_next = true;
+            }
+        }
+        after_terminal = matches!(c, '.' | '!' | '?' | '…')
+            || (after_terminal && matches!(c, '"' | '\'' | ')'));
+    }
+
+    normalized
+}
+
+const DEFAULT_COALESCE_MAX_GAP_SECS: f64 = 0.8;
+const DEFAULT_COALESCE_MAX_CHARS: usize = 200;
+
+pub(crate) fn ends_sentence(text: &str) -> bool {
+    text.trim_end()
+        .trim_end_matches(|c| c == '"' || c == '\'' || c == ')')
+        .ends_with(|c| c == '.' || c == '!' || c == '?' || c == '…')
+}
+
+/// Merge fragmentary Whisper segments into sentence-like units
+/// Adjacent segments are joined while the gap between them is at most `max_gap_secs`,
+/// the previous text doesn't end a sentence, and the result stays within `max_chars`.
+pub fn merge_segments(
+    segments: Vec<TranscriptionSegment>,
+    max_gap_secs: f64,
+    max_chars: usize,
+) -> Vec<TranscriptionSegment> {
+    let mut merged: Vec<TranscriptionSegment> = Vec::with_capacity(segments.len());
+
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        if let Some(last) = merged.last_mut() {
+            let gap = segment.start - last.end;
+            let fits = last.text.chars().count() + 1 + text.chars().count() <= max_chars;
+            if gap <= max_gap_secs && fits && !ends_sentence(&last.text) {
+                last.text.push(' ');
+                last.text.push_str(text);
+                last.start = last.start.min(segment.start);
+                last.end = last.end.max(segment.end);
+                continue;
+            }
+        }
+
+        merged.push(TranscriptionSegment {
+            text: text.to_string(),
+            start: segment.start,
+            end: segment.end,
+        });
+    }
+
+    merged
+}
+
+#[tauri::command]
+pub async fn coalesce_segments(
+    segments: Vec<TranscriptionSegment>,
+    max_gap_secs: Option<f64>,
+    max_chars: Option<usize>,
+) -> Result<Vec<TranscriptionSegment>, String> {
+    Ok(merge_segments(
+        segments,
+        max_gap_secs.unwrap_or(DEFAULT_COALESCE_MAX_GAP_SECS),
+        max_chars.unwrap_or(DEFAULT_COALESCE_MAX_CHARS),
+    ))
+}
+
+/// An edit applied by `resegment`; indices refer to the list as it is when the operation runs
+#[derive(serde::Deserialize, Debug)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum SegmentOperation {
+    /// Split segment `index` at time `at` (seconds, strictly inside the segment); the text
+    /// is divided at the word boundary closest to the same proportion of the segment
+    Split { index: usize, at: f64 },
+    /// Merge segment `index` with the segment after it
+    MergeNext { index: usize },
+}
+
+fn split_segment(
+    segments: &mut Vec<TranscriptionSegment>,
+    index: usize,
+    at: f64,
+) -> Result<(), String> {
+    let segment = segments
+        .get(index)
+        .ok_or_else(|| format!("Split index {} is out of range ({} segments)", index, segments.len()))?;
+    if at.is_nan() || at <= segment.start || at >= segment.end {
+        return Err(format!(
+            "Split time {} is not inside segment {} ({} - {})",
+            at, index, segment.start, segment.end
+        ));
+    }
+
+    let words: Vec<&str> = segment.text.split_whitespace().collect();
+    if words.len() < 2 {
+        return Err(format!("Segment {} has too few words to split", index));
+    }
+    let fraction = (at - segment.start) / (segment.end - segment.start);
+    let split_at = ((words.len() as f64 * fraction).round() as usize).clamp(1, words.len() - 1);
+
+    let first = TranscriptionSegment {
+        text: words[..split_at].join(" "),
+        start: segment.start,
+        end: at,
+    };
+    let second = TranscriptionSegment {
+        text: words[split_at..].join(" "),
+        start: at,
+        end: segment.end,
+    };
+    segments.splice(index..=index, [first, second]);
+    Ok(())
+}
+
+fn merge_with_next(segments: &mut Vec<TranscriptionSegment>, index: usize) -> Result<(), String> {
+    if index + 1 >= segments.len() {
+        return Err(format!(
+            "Merge index {} has no following segment ({} segments)",
+            index,
+            segments.len()
+        ));
+    }
+    let next = segments.remove(index + 1);
+    let segment = &mut segments[index];
+    let next_text = next.text.trim();
+    if !next_text.is_empty() {
+        if !segment.text.trim().is_empty() {
+            segment.text = format!("{} {}", segment.text.trim_end(), next_text);
+        } else {
+            segment.text = next_text.to_string();
+        }
+    }
+    segment.start = segment.start.min(next.start);
+    segment.end = segment.end.max(next.end);
+    Ok(())
+}
+
+/// Apply split/merge edits, then sort by start time and clamp timings so segments never
+/// overlap and never end before they start
+pub fn apply_segment_operations(
+    mut segments: Vec<TranscriptionSegment>,
+    operations: &[SegmentOperation],
+) -> Result<Vec<TranscriptionSegment>, String> {
+    for operation in operations {
+        match *operation {
+            SegmentOperation::Split { index, at } => split_segment(&mut segments, index, at)?,
+            SegmentOperation::MergeNext { index } => merge_with_next(&mut segments, index)?,
+        }
+    }
+
+    segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+    let mut previous_end = f64::NEG_INFINITY;
+    for segment in &mut segments {
+        segment.start = segment.start.max(previous_end).max(0.0);
+        segment.end = segment.end.max(segment.start);
+        previous_end = segment.end;
+    }
+
+    Ok(segments)
+}
+
+/// Re-time a transcript after manual edits (see `SegmentOperation`)
+#[tauri::command]
+pub async fn resegment(
+    segments: Vec<TranscriptionSegment>,
+    operations: Vec<SegmentOperation>,
+) -> Result<Vec<TranscriptionSegment>, String> {
+    apply_segment_operations(segments, &operations)
+}
+
+/// Average silent reading speed used for the reading-time estimate
+const READING_WORDS_PER_MINUTE: f64 = 200.0;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TranscriptStats {
+    pub word_count: usize,
+    pub char_count: usize,
+    pub duration_secs: f64,
+    pub estimated_reading_minutes: f64,
+}
+
+/// Length stats for a transcript; duration runs from the first segment's start to the last one's end
+#[tauri::command]
+pub async fn transcript_stats(segments: Vec<TranscriptionSegment>) -> Result<TranscriptStats, String> {
+    let word_count = segments
+        .iter()
+        .map(|s| s.text.split_whitespace().count())
+        .sum::<usize>();
+    let char_count = segments
+        .iter()
+        .map(|s| s.text.trim().chars().count())
+        .sum::<usize>();
+    let duration_secs = match (segments.first(), segments.last()) {
+        (Some(first), Some(last)) => (last.end - first.start).max(0.0),
+        _ => 0.0,
+    };
+
+    Ok(TranscriptStats {
+        word_count,
+        char_count,
+        duration_secs,
+        estimated_reading_minutes: word_count as f64 / READING_WORDS_PER_MINUTE,
+    })
+}
+
+/// Largest word grid `diff_transcripts` will align (about 4000 x 4000 words)
+const MAX_DIFF_CELLS: usize = 16_000_000;
+
+/// One step of a word-level alignment between two transcripts
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum WordDiff {
+    Equal { word: String },
+    /// Word only in `b`
+    Insert { word: String },
+    /// Word only in `a`
+    Delete { word: String },
+    Substitute { from: String, to: String },
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct TranscriptDiff {
+    pub ops: Vec<WordDiff>,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub substitutions: usize,
+    /// Words in `a`, the denominator of the error rate
+    pub reference_words: usize,
+    /// (substitutions + deletions + insertions) / reference_words
+    pub word_error_rate: f64,
+}
+
+/// Words compare case- and punctuation-insensitively, so "Hello," matches "hello"
+fn diff_key(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric() || *c == '\'')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Minimum-edit word alignment of `a` (reference) against `b` (hypothesis)
+pub fn diff_words(a: &[&str], b: &[&str]) -> Result<TranscriptDiff, String> {
+    let (n, m) = (a.len(), b.len());
+    if (n + 1).saturating_mul(m + 1) > MAX_DIFF_CELLS {
+        return Err(format!(
+            "Transcripts are too long to compare ({} x {} words)",
+            n, m
+        ));
+    }
+    let a_keys: Vec<String> = a.iter().map(|w| diff_key(w)).collect();
+    let b_keys: Vec<String> = b.iter().map(|w| diff_key(w)).collect();
+
+    // cost[i][j] = edits to turn the first i words of a into the first j words of b
+    let width = m + 1;
+    let mut cost = vec![0u32; (n + 1) * width];
+    for i in 0..=n {
+        cost[i * width] = i as u32;
+    }
+    for j in 0..=m {
+        cost[j] = j as u32;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution = cost[(i - 1) * width + j - 1] + u32::from(a_keys[i - 1] != b_keys[j - 1]);
+            let deletion = cost[(i - 1) * width + j] + 1;
+            let insertion = cost[i * width + j - 1] + 1;
+            cost[i * width + j] = substitution.min(deletion).min(insertion);
+        }
+    }
+
+    // Walk back from the end. Among equally cheap paths take matches first, then
+    // insertions/deletions, so an extra word doesn't turn the rest into substitutions.
+    let mut ops = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        let here = cost[i * width + j];
+        let diagonal = (i > 0 && j > 0).then(|| cost[(i - 1) * width + j - 1]);
+        if i > 0 && j > 0 && a_keys[i - 1] == b_keys[j - 1] && diagonal == Some(here) {
+            ops.push(WordDiff::Equal { word: b[j - 1].to_string() });
+            i -= 1;
+            j -= 1;
+        } else if j > 0 && here == cost[i * width + j - 1] + 1 {
+            ops.push(WordDiff::Insert { word: b[j - 1].to_string() });
+            j -= 1;
+        } else if i > 0 && here == cost[(i - 1) * width + j] + 1 {
+            ops.push(WordDiff::Delete { word: a[i - 1].to_string() });
+            i -= 1;
+        } else {
+            ops.push(WordDiff::Substitute {
+                from: a[i - 1].to_string(),
+                to: b[j - 1].to_string(),
+            });
+            i -= 1;
+            j -= 1;
+        }
+    }
+    ops.reverse();
+
+    let count = |pred: fn(&WordDiff) -> bool| ops.iter().filter(|op| pred(op)).count();
+    let insertions = count(|op| matches!(op, WordDiff::Insert { .. }));
+    let deletions = count(|op| matches!(op, WordDiff::Delete { .. }));
+    let substitutions = count(|op| matches!(op, WordDiff::Substitute { .. }));
+    let errors = insertions + deletions + substitutions;
+    // With an empty reference any extra word is a total miss
+    let word_error_rate = match (n, errors) {
+        (_, 0) => 0.0,
+        (0, _) => 1.0,
+        _ => errors as f64 / n as f64,
+    };
+
+    Ok(TranscriptDiff {
+        ops,
+        insertions,
+        deletions,
+        substitutions,
+        reference_words: n,
+        word_error_rate,
+    })
+}
+
+/// Word-level diff and word error rate of transcript `b` against reference `a`, e.g. to see
+/// whether a bigger model or denoising changed the output. Comparison ignores case and
+/// punctuation; timestamps are not compared.
+#[tauri::command]
+pub async fn diff_transcripts(
+    a: Vec<TranscriptionSegment>,
+    b: Vec<TranscriptionSegment>,
+) -> Result<TranscriptDiff, String> {
+    let words = |segments: &[TranscriptionSegment]| -> Vec<String> {
+        segments
+            .iter()
+            .flat_map(|s| s.text.split_whitespace())
+            .filter(|w| !diff_key(w).is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let (a_words, b_words) = (words(&a), words(&b));
+    let a_refs: Vec<&str> = a_words.iter().map(String::as_str).collect();
+    let b_refs: Vec<&str> = b_words.iter().map(String::as_str).collect();
+    diff_words(&a_refs, &b_refs)
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct TranscriptionSegment {
     pub text: String,
@@ -323,6 +1320,16 @@ pub struct TranscriptionSegment {
     pub end: f64,
 }
 
+/// A single decoded token; times are in seconds
+#[derive(serde::Serialize, Debug)]
+pub struct WhisperToken {
+    pub token_text: String,
+    pub t0: f64,
+    pub t1: f64,
+    pub prob: f32,
+    pub is_special: bool,
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct WhisperStatus {
     pub initialized: bool,
@@ -335,4 +1342,271 @@ pub struct ModelPaths {
     pub app_data_dir: String,
     pub resource_exists: bool,
     pub app_data_exists: bool,
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, start: f64, end: f64) -> TranscriptionSegment {
+        TranscriptionSegment {
+            text: text.to_string(),
+            start,
+            end,
+        }
+    }
+
+    /// (text, start, end) of each segment, for comparing whole lists
+    fn parts(segments: &[TranscriptionSegment]) -> Vec<(&str, f64, f64)> {
+        segments.iter().map(|s| (s.text.as_str(), s.start, s.end)).collect()
+    }
+
+    #[test]
+    fn merge_segments_joins_fragments_within_gap() {
+        let segments = vec![
+            segment("So", 0.0, 0.5),
+            segment("I think", 0.9, 1.6),
+            segment("we should go.", 2.0, 3.0),
+        ];
+        let merged = merge_segments(segments, 0.8, 200);
+        assert_eq!(parts(&merged), vec![("So I think we should go.", 0.0, 3.0)]);
+    }
+
+    #[test]
+    fn merge_segments_splits_on_long_gap() {
+        let segments = vec![segment("So", 0.0, 0.5), segment("I think", 1.5, 2.0)];
+        let merged = merge_segments(segments, 0.8, 200);
+        assert_eq!(parts(&merged), vec![("So", 0.0, 0.5), ("I think", 1.5, 2.0)]);
+    }
+
+    #[test]
+    fn merge_segments_keeps_sentence_boundaries() {
+        let segments = vec![
+            segment("Hello there.", 0.0, 1.0),
+            segment("How are", 1.1, 1.5),
+            segment("you?\"", 1.6, 2.0),
+            segment("Fine", 2.1, 2.5),
+        ];
+        let merged = merge_segments(segments, 0.8, 200);
+        assert_eq!(
+            parts(&merged),
+            vec![("Hello there.", 0.0, 1.0), ("How are you?\"", 1.1, 2.0), ("Fine", 2.1, 2.5)]
+        );
+    }
+
+    #[test]
+    fn merge_segments_respects_max_chars() {
+        let segments = || vec![segment("abcde", 0.0, 1.0), segment("fghij", 1.0, 2.0)];
+        // "abcde fghij" is 11 characters
+        assert_eq!(merge_segments(segments(), 0.8, 10).len(), 2);
+        assert_eq!(parts(&merge_segments(segments(), 0.8, 11)), vec![("abcde fghij", 0.0, 2.0)]);
+    }
+
+    #[test]
+    fn merge_segments_drops_empty_segments() {
+        let segments = vec![
+            segment("  ", 0.0, 0.5),
+            segment(" one ", 0.5, 1.0),
+            segment("", 1.0, 1.2),
+            segment("two", 1.3, 2.0),
+        ];
+        let merged = merge_segments(segments, 0.8, 200);
+        assert_eq!(parts(&merged), vec![("one two", 0.5, 2.0)]);
+    }
+
+    fn split(index: usize, at: f64) -> SegmentOperation {
+        SegmentOperation::Split { index, at }
+    }
+
+    #[test]
+    fn split_divides_words_in_proportion_to_time() {
+        let segments = vec![segment("one two three four", 0.0, 4.0)];
+        let result = apply_segment_operations(segments, &[split(0, 1.0)]).unwrap();
+        assert_eq!(parts(&result), vec![("one", 0.0, 1.0), ("two three four", 1.0, 4.0)]);
+    }
+
+    #[test]
+    fn split_keeps_at_least_one_word_on_each_side() {
+        let segments = vec![segment("one two three four", 0.0, 4.0)];
+        let result = apply_segment_operations(segments, &[split(0, 3.9)]).unwrap();
+        assert_eq!(parts(&result), vec![("one two three", 0.0, 3.9), ("four", 3.9, 4.0)]);
+
+        let segments = vec![segment("one two three four", 0.0, 4.0)];
+        let result = apply_segment_operations(segments, &[split(0, 0.1)]).unwrap();
+        assert_eq!(parts(&result), vec![("one", 0.0, 0.1), ("two three four", 0.1, 4.0)]);
+    }
+
+    #[test]
+    fn split_rejects_bad_index_or_time() {
+        let segments = || vec![segment("one two", 1.0, 3.0)];
+        assert!(apply_segment_operations(segments(), &[split(1, 2.0)]).is_err());
+        assert!(apply_segment_operations(segments(), &[split(0, 1.0)]).is_err());
+        assert!(apply_segment_operations(segments(), &[split(0, 3.0)]).is_err());
+        assert!(apply_segment_operations(segments(), &[split(0, 0.5)]).is_err());
+        assert!(apply_segment_operations(segments(), &[split(0, f64::NAN)]).is_err());
+
+        let single_word = vec![segment("one", 1.0, 3.0)];
+        assert!(apply_segment_operations(single_word, &[split(0, 2.0)]).is_err());
+    }
+
+    #[test]
+    fn merge_next_joins_text_and_timing() {
+        let segments = vec![
+            segment("one", 0.0, 1.0),
+            segment("two", 1.2, 2.0),
+            segment("three", 2.0, 3.0),
+        ];
+        let merge = SegmentOperation::MergeNext { index: 1 };
+        let result = apply_segment_operations(segments, &[merge]).unwrap();
+        assert_eq!(parts(&result), vec![("one", 0.0, 1.0), ("two three", 1.2, 3.0)]);
+    }
+
+    #[test]
+    fn merge_next_rejects_last_index() {
+        let segments = vec![segment("one", 0.0, 1.0), segment("two", 1.0, 2.0)];
+        let merge = SegmentOperation::MergeNext { index: 1 };
+        assert!(apply_segment_operations(segments, &[merge]).is_err());
+    }
+
+    #[test]
+    fn operations_apply_in_order() {
+        let segments = vec![segment("one two three four", 0.0, 4.0)];
+        let operations = [split(0, 2.0), split(1, 3.0), SegmentOperation::MergeNext { index: 0 }];
+        let result = apply_segment_operations(segments, &operations).unwrap();
+        assert_eq!(parts(&result), vec![("one two three", 0.0, 3.0), ("four", 3.0, 4.0)]);
+    }
+
+    #[test]
+    fn segments_are_sorted_and_clamped_to_not_overlap() {
+        let segments = vec![
+            segment("b", 2.0, 5.0),
+            segment("a", -1.0, 3.0),
+            segment("c", 4.0, 4.5),
+        ];
+        let result = apply_segment_operations(segments, &[]).unwrap();
+        assert_eq!(parts(&result), vec![("a", 0.0, 3.0), ("b", 3.0, 5.0), ("c", 5.0, 5.0)]);
+    }
+
+    fn equal(word: &str) -> WordDiff {
+        WordDiff::Equal { word: word.to_string() }
+    }
+
+    #[test]
+    fn diff_words_identical_has_no_errors() {
+        let diff = diff_words(&["the", "cat"], &["the", "cat"]).unwrap();
+        assert_eq!(diff.ops, vec![equal("the"), equal("cat")]);
+        assert_eq!(diff.word_error_rate, 0.0);
+    }
+
+    #[test]
+    fn diff_words_aligns_insertion() {
+        let diff = diff_words(&["the", "cat", "sat"], &["the", "black", "cat", "sat"]).unwrap();
+        assert_eq!(
+            diff.ops,
+            vec![
+                equal("the"),
+                WordDiff::Insert { word: "black".to_string() },
+                equal("cat"),
+                equal("sat"),
+            ]
+        );
+        assert_eq!((diff.insertions, diff.deletions, diff.substitutions), (1, 0, 0));
+        assert!((diff.word_error_rate - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn diff_words_aligns_deletion() {
+        let diff = diff_words(&["the", "black", "cat", "sat"], &["the", "cat", "sat"]).unwrap();
+        assert_eq!(
+            diff.ops,
+            vec![
+                equal("the"),
+                WordDiff::Delete { word: "black".to_string() },
+                equal("cat"),
+                equal("sat"),
+            ]
+        );
+        assert_eq!((diff.insertions, diff.deletions, diff.substitutions), (0, 1, 0));
+        assert_eq!(diff.word_error_rate, 0.25);
+    }
+
+    #[test]
+    fn diff_words_aligns_substitution() {
+        let diff = diff_words(&["the", "cat", "sat"], &["the", "dog", "sat"]).unwrap();
+        assert_eq!(
+            diff.ops,
+            vec![
+                equal("the"),
+                WordDiff::Substitute {
+                    from: "cat".to_string(),
+                    to: "dog".to_string(),
+                },
+                equal("sat"),
+            ]
+        );
+        assert_eq!((diff.insertions, diff.deletions, diff.substitutions), (0, 0, 1));
+    }
+
+    #[test]
+    fn diff_words_ignores_case_and_punctuation() {
+        let diff = diff_words(&["Hello,", "World!"], &["hello", "world"]).unwrap();
+        assert_eq!(diff.ops, vec![equal("hello"), equal("world")]);
+        assert_eq!(diff.word_error_rate, 0.0);
+    }
+
+    #[test]
+    fn diff_words_empty_reference() {
+        let diff = diff_words(&[], &["hi", "there"]).unwrap();
+        assert_eq!(diff.insertions, 2);
+        assert_eq!(diff.reference_words, 0);
+        assert_eq!(diff.word_error_rate, 1.0);
+
+        let diff = diff_words(&[], &[]).unwrap();
+        assert!(diff.ops.is_empty());
+        assert_eq!(diff.word_error_rate, 0.0);
+    }
+
+    #[test]
+    fn diff_words_rejects_oversized_input() {
+        // 4001 x 4001 cells is just over MAX_DIFF_CELLS
+        let words = vec!["word"; 4000];
+        assert!(diff_words(&words, &words).is_err());
+        let words = vec!["word"; 3999];
+        assert!(diff_words(&words, &words[..1]).is_ok());
+    }
+
+    #[test]
+    fn normalize_capitalizes_after_terminal_punctuation_and_space() {
+        assert_eq!(
+            normalize_transcript_text("hello there.  how are you ? fine!", None, true),
+            "Hello there. How are you? Fine!"
+        );
+        assert_eq!(
+            normalize_transcript_text(r#"he said "stop." then left"#, None, true),
+            r#"He said "stop." Then left"#
+        );
+    }
+
+    #[test]
+    fn normalize_leaves_abbreviations_alone() {
+        assert_eq!(
+            normalize_transcript_text("some fruits, e.g., apples", None, true),
+            "Some fruits, e.g., apples"
+        );
+    }
+
+    #[test]
+    fn normalize_leaves_urls_and_decimals_alone() {
+        assert_eq!(
+            normalize_transcript_text("go to example.com and pay 3.50 now", None, false),
+            "go to example.com and pay 3.50 now"
+        );
+    }
+
+    #[test]
+    fn normalize_keeps_french_spacing_and_capitalizes_after_it() {
+        assert_eq!(
+            normalize_transcript_text("bonjour ! ça va ? oui", Some("fr"), true),
+            "Bonjour ! Ça va ? Oui"
+        );
+    }
+}
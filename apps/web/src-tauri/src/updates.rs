@@ -0,0 +1,103 @@
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_updater::UpdaterExt;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UpdateInfo {
+    pub available: bool,
+    pub current_version: String,
+    pub version: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct UpdateProgressPayload {
+    downloaded: u64,
+    total: Option<u64>,
+    finished: bool,
+}
+
+/// Check the configured update endpoint without downloading anything
+#[tauri::command]
+pub async fn check_for_update<R: Runtime>(app: AppHandle<R>) -> Result<UpdateInfo, String> {
+    let current_version = app.package_info().version.to_string();
+
+    let update = app
+        .updater()
+        .map_err(|e| format!("Updater not available: {}", e))?
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?;
+
+    Ok(match update {
+        Some(update) => UpdateInfo {
+            available: true,
+            current_version,
+            version: Some(update.version.clone()),
+            notes: update.body.clone(),
+        },
+        None => UpdateInfo {
+            available: false,
+            current_version,
+            version: None,
+            notes: None,
+        },
+    })
+}
+
+/// Download and install the latest update, emitting `update_progress` events along the way
+/// Returns false if there was no update to install
+#[tauri::command]
+pub async fn install_update<R: Runtime>(app: AppHandle<R>) -> Result<bool, String> {
+    let Some(update) = app
+        .updater()
+        .map_err(|e| format!("Updater not available: {}", e))?
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?
+    else {
+        return Ok(false);
+    };
+
+    log::info!("Installing update {}", update.version);
+
+    // (downloaded, total) shared with the finish callback so the last event keeps the bar full
+    let progress = Arc::new(Mutex::new((0u64, None::<u64>)));
+    let finished_progress = progress.clone();
+    let progress_app = app.clone();
+    let finished_app = app.clone();
+
+    update
+        .download_and_install(
+            move |chunk_length, content_length| {
+                let mut progress = progress.lock().unwrap();
+                progress.0 += chunk_length as u64;
+                progress.1 = content_length.or(progress.1);
+                let _ = progress_app.emit(
+                    "update_progress",
+                    UpdateProgressPayload {
+                        downloaded: progress.0,
+                        total: progress.1,
+                        finished: false,
+                    },
+                );
+            },
+            move || {
+                let (downloaded, total) = *finished_progress.lock().unwrap();
+                let _ = finished_app.emit(
+                    "update_progress",
+                    UpdateProgressPayload {
+                        downloaded,
+                        total,
+                        finished: true,
+                    },
+                );
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to install update: {}", e))?;
+
+    Ok(true)
+}
@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core'
+import type { DecodeTuning } from './whisper'
 
 export interface TranscriptionSegment {
   text: string
@@ -6,12 +7,67 @@ export interface TranscriptionSegment {
   end: number
 }
 
+export interface RenderDevice {
+  id: string
+  name: string
+  is_default: boolean
+}
+
+/**
+ * List output devices whose audio can be recorded (Windows only)
+ */
+export async function listRenderDevices(): Promise<RenderDevice[]> {
+  return await invoke<RenderDevice[]>('list_render_devices')
+}
+
 /**
  * Start recording system audio (non-real-time)
  * Captures desktop/system audio without permission prompts
+ * @param deviceId - Output device to capture (from listRenderDevices); defaults to the system default
+ */
+export async function startSystemAudioRecording(deviceId?: string): Promise<void> {
+  return await invoke('start_system_audio_recording', { deviceId: deviceId ?? null })
+}
+
+/** Which sources of a mixed recording feed Whisper */
+export interface TranscribeSources {
+  mic: boolean
+  system: boolean
+}
+
+/**
+ * Start recording the microphone and system audio mixed together (Windows only)
+ * Stop it with stopSystemAudioRecordingAndTranscribe / stopSystemAudioRecordingAndSave
+ * @param micGain - Microphone volume multiplier (0-4, default 1)
+ * @param systemGain - System audio volume multiplier (0-4, default 1)
+ * @param deviceId - Output device to capture (from listRenderDevices)
+ * @param transcribeSources - Sources that get transcribed (default mic only); both are always recorded
  */
-export async function startSystemAudioRecording(): Promise<void> {
-  return await invoke('start_system_audio_recording')
+export async function startMixedRecording(
+  micGain?: number,
+  systemGain?: number,
+  deviceId?: string,
+  transcribeSources?: TranscribeSources,
+): Promise<void> {
+  return await invoke('start_mixed_recording', {
+    micGain: micGain ?? null,
+    systemGain: systemGain ?? null,
+    deviceId: deviceId ?? null,
+    transcribeSources: transcribeSources ?? null,
+  })
+}
+
+export interface RecordingTranscription {
+  segments: TranscriptionSegment[]
+  saved_path: string | null
+  transcription_id: string | null
+}
+
+/** Where to store the transcription server-side when stopping a recording */
+export interface SaveTranscriptionTarget {
+  user_id: string
+  conversation_id?: string | null
+  title?: string | null
 }
 
 /**
@@ -19,5 +75,30 @@ export async function startSystemAudioRecording(): Promise<void> {
  * Returns transcription segments with timestamps
  */
 export async function stopSystemAudioRecordingAndTranscribe(): Promise<TranscriptionSegment[]> {
-  return await invoke<TranscriptionSegment[]>('stop_system_audio_recording_and_transcribe')
+  const result = await stopSystemAudioRecordingAndSave()
+  return result.segments
+}
+
+/**
+ * Stop recording system audio, optionally save it as a WAV file, and transcribe it
+ * Returns the transcription segments and the saved file path (if any)
+ * @param saveToDb - Also store the transcription and its segments in the database
+ * @param bitDepth - WAV sample format: 16-bit integer (default, smaller) or 32-bit float
+ * @param tuning - Optional decoding thresholds for hallucination control
+ * @param normalizePeak - Peak level (0-1] the audio is normalized to before transcription (default 0.8)
+ */
+export async function stopSystemAudioRecordingAndSave(
+  savePath?: string,
+  saveToDb?: SaveTranscriptionTarget,
+  bitDepth?: 16 | 32,
+  tuning?: DecodeTuning,
+  normalizePeak?: number,
+): Promise<RecordingTranscription> {
+  return await invoke<RecordingTranscription>('stop_system_audio_recording_and_transcribe', {
+    savePath: savePath ?? null,
+    saveToDb: saveToDb ?? null,
+    bitDepth: bitDepth ?? null,
+    tuning: tuning ?? null,
+    normalizePeak: normalizePeak ?? null,
+  })
 }
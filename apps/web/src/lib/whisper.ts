@@ -6,6 +6,14 @@ export interface TranscriptionSegment {
   end: number
 }
 
+/** whisper.cpp anti-hallucination thresholds; omitted fields keep whisper's defaults */
+export interface DecodeTuning {
+  no_speech_thold?: number
+  entropy_thold?: number
+  logprob_thold?: number
+  temperature_inc?: number
+}
+
 export interface WhisperStatus {
   initialized: boolean
   model_path: string | null
@@ -14,41 +22,169 @@ export interface WhisperStatus {
 /**
  * Initialize Whisper model
  * @param modelName - Name of the model file (e.g., 'ggml-base.en.bin')
+ * @param fallback - Fall back to the bundled base model if the requested one is missing
+ * @param timeoutSecs - Give up if loading takes longer than this (default 120s)
+ *
+ * Emits `model_loading` (every second) and `model_loaded` with `{ model_name, elapsed_ms }`.
  */
-export async function initializeWhisper(modelName: string): Promise<string> {
-  return await invoke<string>('initialize_whisper', { modelName })
+export async function initializeWhisper(
+  modelName: string,
+  fallback?: boolean,
+  timeoutSecs?: number,
+): Promise<string> {
+  return await invoke<string>('initialize_whisper', { modelName, fallback, timeoutSecs })
+}
+
+/**
+ * Initialize Whisper from the model file's contents instead of a path
+ * @param modelBytes - The ggml model data
+ * @param modelName - Name to cache the model under (usable as `activeModel` later)
+ */
+export async function initializeWhisperFromBytes(
+  modelBytes: Uint8Array,
+  modelName: string,
+): Promise<string> {
+  return await invoke<string>('initialize_whisper_from_bytes', {
+    modelBytes: Array.from(modelBytes),
+    modelName,
+  })
 }
 
 /**
  * Transcribe audio file
  * @param audioPath - Full path to the WAV audio file
  * @param language - Optional language code (e.g., 'en', 'es', 'fr')
+ * @param tuning - Optional decoding thresholds for hallucination control
+ * @param activeModel - Use this loaded model instead of the active one
  */
 export async function transcribeAudio(
   audioPath: string,
   language?: string,
+  tuning?: DecodeTuning,
+  activeModel?: string,
 ): Promise<string> {
-  return await invoke<string>('transcribe_audio', { audioPath, language })
+  return await invoke<string>('transcribe_audio', { audioPath, language, tuning, activeModel })
 }
 
 /**
  * Transcribe audio with timestamps
  * @param audioPath - Full path to the WAV audio file
  * @param language - Optional language code
+ * @param tuning - Optional decoding thresholds for hallucination control
+ * @param activeModel - Use this loaded model instead of the active one
  */
 export async function transcribeAudioWithTimestamps(
   audioPath: string,
   language?: string,
+  tuning?: DecodeTuning,
+  activeModel?: string,
 ): Promise<TranscriptionSegment[]> {
   return await invoke<TranscriptionSegment[]>(
     'transcribe_audio_with_timestamps',
     {
       audioPath,
       language,
+      tuning,
+      activeModel,
     },
   )
 }
 
+/** A single decoded token; t0/t1 are in seconds */
+export interface WhisperToken {
+  token_text: string
+  t0: number
+  t1: number
+  prob: number
+  is_special: boolean
+}
+
+/**
+ * Transcribe audio and return every token with its timing and probability
+ * Useful for word highlighting during playback or confidence coloring
+ * @param audioPath - Full path to a 16kHz WAV audio file
+ * @param language - Optional language code
+ * @param tuning - Optional decoding thresholds for hallucination control
+ * @param activeModel - Use this loaded model instead of the active one
+ */
+export async function transcribeAudioTokens(
+  audioPath: string,
+  language?: string,
+  tuning?: DecodeTuning,
+  activeModel?: string,
+): Promise<WhisperToken[]> {
+  return await invoke<WhisperToken[]>('transcribe_audio_tokens', {
+    audioPath,
+    language,
+    tuning,
+    activeModel,
+  })
+}
+
+/**
+ * Transcribe a base64-encoded WAV (any sample rate or channel count) with timestamps
+ * @param audioBase64 - WAV file contents, base64 encoded
+ * @param language - Optional language code (default "en")
+ * @param normalizePeak - Peak level (0-1] the audio is normalized to (default 0.8)
+ */
+export async function transcribeAudioLocalWithTimestamps(
+  audioBase64: string,
+  language?: string,
+  normalizePeak?: number,
+): Promise<TranscriptionSegment[]> {
+  return await invoke<TranscriptionSegment[]>('transcribe_audio_local_with_timestamps', {
+    audioBase64,
+    language,
+    normalizePeak,
+  })
+}
+
+export interface LoadedModel {
+  name: string
+  active: boolean
+}
+
+/**
+ * List the models held in memory (each loaded once with initializeWhisper)
+ */
+export async function listLoadedModels(): Promise<LoadedModel[]> {
+  return await invoke<LoadedModel[]>('list_loaded_models')
+}
+
+/**
+ * Switch the default model to one that is already loaded, without reloading it
+ * @param modelName - Name of a loaded model (see listLoadedModels)
+ */
+export async function setActiveModel(modelName: string): Promise<void> {
+  await invoke('set_active_model', { modelName })
+}
+
+export type RemoteProvider = 'openai' | 'deepgram'
+
+/**
+ * Transcribe audio with a cloud provider instead of the local model
+ * @param audioBase64 - Base64-encoded audio (a data URL is also accepted)
+ * @param provider - 'openai' (Whisper API) or 'deepgram'
+ * @param apiKey - API key for the chosen provider
+ * @param language - Optional language code
+ * @param mimeType - Audio type (e.g. 'audio/webm'); detected from the data URL or the bytes when omitted
+ */
+export async function transcribeRemote(
+  audioBase64: string,
+  provider: RemoteProvider,
+  apiKey: string,
+  language?: string,
+  mimeType?: string,
+): Promise<TranscriptionSegment[]> {
+  return await invoke<TranscriptionSegment[]>('transcribe_remote', {
+    audioBase64,
+    provider,
+    apiKey,
+    language,
+    mimeType,
+  })
+}
+
 /**
  * Check if Whisper is initialized
  */
@@ -56,6 +192,28 @@ export async function checkWhisperStatus(): Promise<WhisperStatus> {
   return await invoke<WhisperStatus>('check_whisper_status')
 }
 
+export interface ModelPathCheck {
+  location: string
+  path: string | null
+  exists: boolean
+}
+
+export interface ModelResolutionReport {
+  model_name: string
+  checked: ModelPathCheck[]
+  resolved: string | null
+  current_dir: string | null
+  executable_path: string | null
+}
+
+/**
+ * Report every location searched for a model (in order) without loading it
+ * @param modelName - Name of the model file (e.g., 'ggml-base.en.bin')
+ */
+export async function debugModelResolution(modelName: string): Promise<ModelResolutionReport> {
+  return await invoke<ModelResolutionReport>('debug_model_resolution', { modelName })
+}
+
 /**
  * Get the models directory path
  */
@@ -85,3 +243,30 @@ export async function saveAudioBuffer(
 export async function cleanupAudioFile(filePath: string): Promise<void> {
   await invoke('cleanup_audio_file', { filePath })
 }
+
+export type PermissionState =
+  | 'granted'
+  | 'denied'
+  | 'unavailable'
+  | 'unsupported'
+  | 'unknown'
+
+export interface AudioPermissions {
+  microphone: PermissionState
+  system_audio: PermissionState
+}
+
+/**
+ * Check whether the microphone and system audio can be captured
+ * On macOS this opens the microphone briefly, which shows the OS prompt the first time.
+ */
+export async function checkAudioPermissions(): Promise<AudioPermissions> {
+  return await invoke<AudioPermissions>('check_audio_permissions')
+}
+
+/**
+ * Ask the OS for microphone access (opens the privacy settings on Windows if denied)
+ */
+export async function requestAudioPermissions(): Promise<AudioPermissions> {
+  return await invoke<AudioPermissions>('request_audio_permissions')
+}
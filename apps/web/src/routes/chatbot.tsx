@@ -37,9 +37,11 @@ interface GroundingMetadata {
 
 // The payload structure sent from Rust
 interface StreamPayload {
+  chat_id: string;
   text?: string;
   is_done: boolean;
   metadata?: GroundingMetadata;
+  search_entry_point?: string; // Sanitized search suggestions HTML
 }
 
 interface ChatMessage {
@@ -181,6 +183,7 @@ function ChatbotComponent() {
 
                 if (searchEntryPoint) {
                   const renderedContent =
+                    payload.search_entry_point ||
                     searchEntryPoint.renderedContent ||
                     (searchEntryPoint as any).rendered_content;
 
@@ -55,6 +55,7 @@ export interface Transcription {
   title?: string | null
   created_at: string
   updated_at: string
+  segment_count?: number
 }
 
 export interface TranscriptionSegment {
@@ -68,15 +69,56 @@ export interface TranscriptionSegment {
 
 // === Conversation Functions ===
 
-export async function getConversations(userId: string): Promise<Conversation[]> {
+export async function getConversations(
+  userId: string,
+  typeFilter?: string,
+): Promise<Conversation[]> {
   try {
-    return await invoke<Conversation[]>('db_get_conversations', { userId })
+    return await invoke<Conversation[]>('db_get_conversations', {
+      userId,
+      typeFilter: typeFilter ?? null,
+    })
   } catch (error) {
     console.error('Failed to get conversations:', error)
     return []
   }
 }
 
+export interface ConversationTypeCount {
+  type: string
+  count: number
+}
+
+/** Distinct conversation types with how many conversations each has, most common first */
+export async function getConversationTypes(userId: string): Promise<ConversationTypeCount[]> {
+  try {
+    return await invoke<ConversationTypeCount[]>('db_get_conversation_types', { userId })
+  } catch (error) {
+    console.error('Failed to get conversation types:', error)
+    return []
+  }
+}
+
+export interface ConversationGroup {
+  group_label: string
+  conversations: Conversation[]
+}
+
+export async function getConversationsGrouped(
+  userId: string,
+  timezone: string = Intl.DateTimeFormat().resolvedOptions().timeZone,
+): Promise<ConversationGroup[]> {
+  try {
+    return await invoke<ConversationGroup[]>('db_get_conversations_grouped', {
+      userId,
+      timezone,
+    })
+  } catch (error) {
+    console.error('Failed to get grouped conversations:', error)
+    return []
+  }
+}
+
 export async function getConversationById(conversationId: string): Promise<Conversation | null> {
   try {
     return await invoke<Conversation | null>('db_get_conversation_by_id', {
@@ -117,6 +159,11 @@ export async function deleteConversation(conversationId: string): Promise<boolea
   }
 }
 
+/** Delete the user's conversations not updated in the last `olderThanDays` days; resolves to the count removed */
+export async function purgeOldConversations(userId: string, olderThanDays: number): Promise<number> {
+  return await invoke<number>('db_purge_old_conversations', { userId, olderThanDays })
+}
+
 export async function getConversationMessages(
   conversationId: string
 ): Promise<ConversationMessage[]> {
@@ -170,6 +217,57 @@ export async function getChatByConversationId(conversationId: string): Promise<C
   }
 }
 
+/** The conversation's transcript as one string, one segment per line (optionally `[mm:ss]`-prefixed) */
+export async function getTranscriptText(
+  conversationId: string,
+  includeTimestamps = false,
+): Promise<string> {
+  return await invoke<string>('db_get_transcript_text', { conversationId, includeTimestamps })
+}
+
+export interface DanglingTranscription {
+  id: string
+  title: string | null
+  conversation_id: string
+  created_at: string
+}
+
+export interface OrphanedSegment {
+  id: string
+  transcription_id: string
+  text: string
+  created_at: string
+}
+
+export interface OrphanScan {
+  transcriptions: DanglingTranscription[]
+  segments: OrphanedSegment[]
+}
+
+export interface OrphanRepairReport {
+  segments_deleted: number
+  transcriptions_deleted: number
+  conversations_created: number
+}
+
+/** The user's transcriptions whose conversation no longer exists, and their segments */
+export async function findOrphans(userId: string): Promise<OrphanScan> {
+  return await invoke<OrphanScan>('db_find_orphans', { userId })
+}
+
+/** Delete dangling transcriptions, or give each one a new conversation so it shows up again */
+export async function repairOrphans(
+  userId: string,
+  strategy: 'delete' | 'create_placeholders',
+): Promise<OrphanRepairReport> {
+  return await invoke<OrphanRepairReport>('db_repair_orphans', { userId, strategy })
+}
+
+/** Create a chat seeded with the conversation's transcript; resolves to the new chat id */
+export async function transcriptionToChat(conversationId: string): Promise<string> {
+  return await invoke<string>('transcription_to_chat', { conversationId })
+}
+
 export async function createChat(input: {
   conversation_id?: string | null
   user_id: string
@@ -205,6 +303,37 @@ export async function getMessages(chatId: string): Promise<Message[]> {
   }
 }
 
+/** Position in a chat's history; `id` breaks ties between messages with the same timestamp */
+export interface MessageCursor {
+  created_at: string
+  id: string
+}
+
+export interface MessagePage {
+  messages: Message[]
+  next_cursor: MessageCursor | null
+}
+
+/**
+ * Load a page of messages older than `before` (newest page when omitted), oldest first
+ */
+export async function getMessagesPaged(
+  chatId: string,
+  before?: MessageCursor | null,
+  limit: number = 50,
+): Promise<MessagePage> {
+  try {
+    return await invoke<MessagePage>('db_get_messages_paged', {
+      chatId,
+      before: before ?? null,
+      limit,
+    })
+  } catch (error) {
+    console.error('Failed to get messages page:', error)
+    return { messages: [], next_cursor: null }
+  }
+}
+
 export async function createMessage(input: {
   chat_id: string
   role: string
@@ -285,6 +414,21 @@ export async function getTranscriptionById(
   }
 }
 
+export async function deleteTranscription(transcriptionId: string): Promise<number> {
+  return await invoke<number>('db_delete_transcription', { transcriptionId })
+}
+
+export async function updateTranscription(
+  transcriptionId: string,
+  updates: { title?: string | null; conversationId?: string | null }
+): Promise<Transcription> {
+  return await invoke<Transcription>('db_update_transcription', {
+    transcriptionId,
+    title: updates.title ?? null,
+    conversationId: updates.conversationId ?? null,
+  })
+}
+
 export async function createTranscription(input: {
   conversation_id?: string | null
   user_id: string
@@ -339,3 +483,32 @@ export async function testConnection(): Promise<boolean> {
   }
 }
 
+
+// === Semantic Search ===
+
+export interface SemanticSearchResult {
+  conversation_id: string | null
+  chat_id: string
+  message_id: string
+  role: string
+  content: string
+  similarity: number
+}
+
+/** Embed a conversation's chat messages for semantic search; resolves to how many were embedded */
+export async function generateEmbeddings(conversationId: string, apiKey?: string): Promise<number> {
+  return await invoke<number>('db_generate_embeddings', { conversationId, apiKey: apiKey ?? null })
+}
+
+export async function semanticSearch(
+  userId: string,
+  query: string,
+  options: { apiKey?: string; limit?: number } = {}
+): Promise<SemanticSearchResult[]> {
+  return await invoke<SemanticSearchResult[]>('db_semantic_search', {
+    userId,
+    query,
+    apiKey: options.apiKey ?? null,
+    limit: options.limit ?? null,
+  })
+}